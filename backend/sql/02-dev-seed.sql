@@ -1,3 +0,0 @@
--- Dev seed
-INSERT INTO groceries (id, cid, cost, name, status) VALUES (100, 123, 25, 'banana', 'basket');
-INSERT INTO groceries (id, cid, cost, name) VALUES (101, 123, 50, 'orange');
@@ -0,0 +1,13 @@
+-- Dev seed
+-- users 'demo' and 'demo2' with password 'welcome'
+INSERT INTO users (id, username, pwd) VALUES (123, 'demo', '$argon2id$v=19$m=19456,t=2,p=1$usBVMp7mlfg4TqsphiTDIA$mzdbi52LaUaQPpC7QfWZLn3jAP2QmBMLIirF81us0XE');
+INSERT INTO users (id, username, pwd) VALUES (124, 'demo2', '$argon2id$v=19$m=19456,t=2,p=1$usBVMp7mlfg4TqsphiTDIA$mzdbi52LaUaQPpC7QfWZLn3jAP2QmBMLIirF81us0XE');
+
+INSERT INTO shopping_lists (id, cid, name) VALUES (10, 123, 'weekly');
+INSERT INTO list_members (list_id, user_id, role, pending) VALUES (10, 123, 'owner', false);
+
+INSERT INTO groceries (id, cid, list_id, cost, name, status) VALUES (100, 123, 10, 25, 'banana', 'basket');
+INSERT INTO groceries (id, cid, list_id, cost, name) VALUES (101, 123, 10, 50, 'orange');
+
+INSERT INTO categories (id, cid, name, position, aisle) VALUES (20, 123, 'produce', 0, '1');
+INSERT INTO categories (id, cid, name, position, aisle) VALUES (21, 123, 'dairy', 1, '4');
@@ -0,0 +1,9 @@
+-- Revert 0001-create-schema (drops all the data)
+DROP TABLE IF EXISTS groceries;
+DROP SEQUENCE IF EXISTS groceries_position_seq;
+DROP TYPE IF EXISTS grocery_status_enum;
+DROP TABLE IF EXISTS list_members;
+DROP TYPE IF EXISTS list_role_enum;
+DROP TABLE IF EXISTS shopping_lists;
+DROP TABLE IF EXISTS sessions;
+DROP TABLE IF EXISTS users;
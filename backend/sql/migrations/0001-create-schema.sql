@@ -0,0 +1,67 @@
+-- Users
+CREATE TABLE users (
+    id bigserial PRIMARY KEY,
+    username text NOT NULL UNIQUE,
+    pwd text NOT NULL, -- argon2 password hash
+    budget bigint, -- spending limit of the groceries without list
+    budget_strict boolean NOT NULL DEFAULT false
+);
+ALTER SEQUENCE users_id_seq RESTART WITH 1000;
+
+-- User sessions
+CREATE TABLE sessions (
+    id bigserial PRIMARY KEY,
+    user_id bigint NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exp bigint NOT NULL, -- expiration (unix seconds)
+    revoked boolean NOT NULL DEFAULT false,
+    ctime timestamptz NOT NULL DEFAULT now()
+);
+
+-- Shopping lists
+CREATE TABLE shopping_lists (
+    id bigserial PRIMARY KEY,
+    cid bigint NOT NULL REFERENCES users(id), -- creator user id
+    name text NOT NULL,
+    budget bigint, -- spending limit
+    budget_strict boolean NOT NULL DEFAULT false
+);
+ALTER SEQUENCE shopping_lists_id_seq RESTART WITH 1000;
+
+-- Shopping list member role enum
+CREATE TYPE list_role_enum AS ENUM (
+    'owner',
+    'editor',
+    'viewer'
+);
+
+-- Shopping list members
+CREATE TABLE list_members (
+    list_id bigint NOT NULL REFERENCES shopping_lists(id) ON DELETE CASCADE,
+    user_id bigint NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role list_role_enum NOT NULL,
+    pending boolean NOT NULL DEFAULT true, -- invitation not yet accepted
+    PRIMARY KEY (list_id, user_id)
+);
+
+-- Item status enum
+CREATE TYPE grocery_status_enum AS ENUM (
+    'shelf',
+    'basket'
+);
+
+-- Grocery position (descending, so new groceries come first)
+CREATE SEQUENCE groceries_position_seq INCREMENT BY -1 MAXVALUE 0 START WITH 0;
+
+-- Groceries
+CREATE TABLE groceries (
+    id bigserial,
+    cid bigint NOT NULL REFERENCES users(id), -- creator user id
+    list_id bigint REFERENCES shopping_lists(id) ON DELETE CASCADE,
+    position bigint NOT NULL DEFAULT nextval('groceries_position_seq'),
+    quantity numeric(12, 3) NOT NULL DEFAULT 1 CHECK (quantity > 0),
+    unit text, -- free text (e.g. 'kg', 'pack'), none for a count
+    cost bigint NOT NULL DEFAULT 0, -- unit price
+    name text NOT NULL,
+    status grocery_status_enum NOT NULL DEFAULT 'shelf'
+);
+ALTER SEQUENCE groceries_id_seq RESTART WITH 1000;
@@ -0,0 +1,3 @@
+-- Revert 0002-create-categories (drops the categories of the groceries)
+ALTER TABLE groceries DROP COLUMN IF EXISTS category_id;
+DROP TABLE IF EXISTS categories;
@@ -0,0 +1,13 @@
+-- Grocery categories (per user, e.g. 'produce', 'dairy'), in the store aisles order
+CREATE TABLE categories (
+    id bigserial PRIMARY KEY,
+    cid bigint NOT NULL REFERENCES users(id) ON DELETE CASCADE, -- owner user id
+    name text NOT NULL,
+    position bigint NOT NULL DEFAULT 0, -- walk order in the store (ascending)
+    aisle text, -- store aisle label (e.g. 'A3'), free text
+    UNIQUE (cid, name)
+);
+ALTER SEQUENCE categories_id_seq RESTART WITH 1000;
+
+-- Grocery category (uncategorized when its category is deleted)
+ALTER TABLE groceries ADD COLUMN category_id bigint REFERENCES categories(id) ON DELETE SET NULL;
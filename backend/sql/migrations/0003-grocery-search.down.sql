@@ -0,0 +1,4 @@
+-- Revert 0003-grocery-search (the pg_trgm extension is kept, it may be used by others)
+DROP INDEX IF EXISTS groceries_name_trgm_idx;
+DROP INDEX IF EXISTS groceries_search_idx;
+ALTER TABLE groceries DROP COLUMN IF EXISTS search;
@@ -0,0 +1,7 @@
+-- Grocery search - full-text (stemmed words) and trigram similarity (typos, spelling variants) on name
+-- Note: pg_trgm is a trusted extension, the db owner can create it
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+ALTER TABLE groceries ADD COLUMN search tsvector GENERATED ALWAYS AS (to_tsvector('english', name)) STORED;
+CREATE INDEX groceries_search_idx ON groceries USING gin (search);
+CREATE INDEX groceries_name_trgm_idx ON groceries USING gin (name gin_trgm_ops);
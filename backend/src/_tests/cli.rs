@@ -0,0 +1,149 @@
+use super::{
+    export, run, to_csv, user, Cli, Command, Error, ExportFormat, MigrateCommand, UserCommand,
+};
+use crate::config::Config;
+use crate::model::{connect_db, init_db, Grocery, GroceryStatus, Quantity, SessionMac, UserMac};
+use crate::security::verify_pwd;
+use crate::web;
+use clap::Parser;
+use rust_decimal::Decimal;
+
+/// Test the command line parsing
+#[test]
+fn cli_parse() -> Result<(), Box<dyn std::error::Error>> {
+    // -- CHECK - no command (serve)
+    let cli = Cli::try_parse_from(["shopping-list"])?;
+    assert!(cli.command.is_none());
+
+    // -- CHECK - migrate
+    let cli = Cli::try_parse_from(["shopping-list", "migrate", "down"])?;
+    assert!(matches!(
+        cli.command,
+        Some(Command::Migrate {
+            command: MigrateCommand::Down
+        })
+    ));
+
+    // -- CHECK - export
+    let cli = Cli::try_parse_from([
+        "shopping-list",
+        "export",
+        "--user",
+        "demo",
+        "--list",
+        "10",
+        "--format",
+        "csv",
+    ])?;
+    match cli.command {
+        Some(Command::Export {
+            user, list, format, ..
+        }) => {
+            assert_eq!("demo", user);
+            assert_eq!(Some(10), list);
+            assert_eq!(ExportFormat::Csv, format);
+        }
+        other => panic!("Wrong Command {:?} ", other),
+    }
+
+    // -- CHECK - unknown command
+    assert!(Cli::try_parse_from(["shopping-list", "unknown"]).is_err());
+
+    Ok(())
+}
+
+/// Test user create and reset-password commands
+#[tokio::test]
+async fn cli_user_create_reset_password() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE (the user command does not migrate)
+    init_db(&Config::load()?.db).await?;
+
+    // -- ACTION - create
+    let cli = Cli::try_parse_from([
+        "shopping-list",
+        "user",
+        "create",
+        "test_cli_user",
+        "--password",
+        "first",
+    ])?;
+    run(cli, Config::load()?).await?;
+
+    // -- CHECK - create
+    let db = connect_db(&Config::load()?.db).await?;
+    let user_auth = UserMac::get_for_auth(&db, "test_cli_user").await?;
+    verify_pwd("first", &user_auth.pwd)?;
+    let session = SessionMac::create(&db, user_auth.id, i64::MAX).await?;
+
+    // -- ACTION - reset password (without db recreate)
+    let command = UserCommand::ResetPassword {
+        username: "test_cli_user".to_string(),
+        password: Some("second".to_string()),
+    };
+    user(&db, command).await?;
+
+    // -- CHECK - reset password
+    let user_auth = UserMac::get_for_auth(&db, "test_cli_user").await?;
+    verify_pwd("second", &user_auth.pwd)?;
+    assert!(verify_pwd("first", &user_auth.pwd).is_err());
+    assert!(SessionMac::get(&db, session.id).await?.revoked);
+
+    // -- ACTION / CHECK - create with a blank username
+    let command = UserCommand::Create {
+        username: "  ".to_string(),
+        password: Some("first".to_string()),
+    };
+    assert!(matches!(
+        user(&db, command).await,
+        Err(Error::Web(web::Error::RegisterEmptyCredentials))
+    ));
+
+    Ok(())
+}
+
+/// Test groceries export of a shopping list
+#[tokio::test]
+async fn cli_export_list() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let file = std::env::temp_dir().join("shopping-list-cli_export_list.json");
+    let output = Some(file.to_str().unwrap().to_string());
+
+    // -- ACTION
+    export(&db, "demo", Some(10), ExportFormat::Json, output).await?;
+
+    // -- CHECK
+    let groceries: Vec<Grocery> = serde_json::from_str(&std::fs::read_to_string(&file)?)?;
+    let names: Vec<&str> = groceries.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(vec!["orange", "banana"], names);
+
+    Ok(())
+}
+
+/// Test groceries csv export
+#[test]
+fn cli_to_csv() {
+    // -- FIXTURE
+    let grocery = Grocery {
+        id: 100,
+        cid: 123,
+        list_id: Some(10),
+        position: -1,
+        quantity: Quantity(Decimal::new(1500, 3)),
+        unit: Some("kg".to_string()),
+        cost: 25,
+        name: "rice, \"basmati\"".to_string(),
+        status: GroceryStatus::Shelf,
+        category_id: None,
+    };
+
+    // -- ACTION
+    let csv = to_csv(&[grocery]);
+
+    // -- CHECK
+    assert_eq!(
+        "id,list_id,position,name,quantity,unit,cost,status\n\
+         100,10,-1,\"rice, \"\"basmati\"\"\",1.5,kg,25,shelf\n",
+        csv
+    );
+}
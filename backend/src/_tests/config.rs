@@ -0,0 +1,132 @@
+use super::{Config, Error, LogFormat};
+use std::collections::HashMap;
+use std::fs;
+
+/// Test the defaults are valid
+#[test]
+fn config_default_valid() {
+    let config = Config::default();
+
+    assert!(config.validate().is_ok());
+    assert_eq!("127.0.0.1", config.web.host.to_string());
+    assert_eq!(8080, config.web.port);
+    assert_eq!(5, config.db.max_connections);
+    assert!(!config.db.recreate);
+    assert_eq!(LogFormat::Text, config.log.format);
+}
+
+/// Test the toml file (missing values are defaulted)
+#[test]
+fn config_from_file() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let file = std::env::temp_dir().join("shopping-list-config_from_file.toml");
+    fs::write(
+        &file,
+        "[web]\nhost = \"0.0.0.0\"\nport = 3000\n\n[db]\nmax_connections = 10\n",
+    )?;
+
+    // -- ACTION
+    let config = Config::from_file(file.to_str().unwrap())?;
+
+    // -- CHECK
+    assert_eq!("0.0.0.0", config.web.host.to_string());
+    assert_eq!(3000, config.web.port);
+    assert_eq!(10, config.db.max_connections);
+    assert_eq!(Config::default().db.url, config.db.url);
+
+    Ok(())
+}
+
+/// Test the toml file with an unknown key
+#[test]
+fn config_from_file_unknown_key() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let file = std::env::temp_dir().join("shopping-list-config_from_file_unknown_key.toml");
+    fs::write(&file, "[web]\nprot = 3000\n")?;
+
+    // -- ACTION
+    let result = Config::from_file(file.to_str().unwrap());
+
+    // -- CHECK
+    match result {
+        Ok(_) => panic!("Should not parse an unknown key"),
+        Err(Error::FileParse(_, ex)) => assert!(ex.to_string().contains("prot")),
+        other_error => panic!("Wrong Error {:?} ", other_error),
+    }
+
+    Ok(())
+}
+
+/// Test the var overrides
+#[test]
+fn config_override_with() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let vars = HashMap::from([
+        ("DATABASE_URL", "postgres://u:p@db:5432/app"),
+        ("WEB_PORT", "9000"),
+        ("DB_RECREATE", "true"),
+        ("MAIL_FILE_DIR", "/tmp/mails"),
+        ("LOG_FORMAT", "json"),
+        ("DATABASE_CONNECT_BACKOFF_MS", "50"),
+        ("DATABASE_CONNECT_MAX_WAIT_MS", "5000"),
+    ]);
+    let mut config = Config::default();
+
+    // -- ACTION
+    config.override_with(|name| vars.get(name).map(|v| v.to_string()))?;
+
+    // -- CHECK
+    assert_eq!("postgres://u:p@db:5432/app", config.db.url);
+    assert_eq!(9000, config.web.port);
+    assert!(config.db.recreate);
+    assert_eq!(Some("/tmp/mails".to_string()), config.mail.file_dir);
+    assert_eq!(LogFormat::Json, config.log.format);
+    assert_eq!(50, config.db.connect_backoff_ms);
+    assert_eq!(5000, config.db.connect_max_wait_ms);
+
+    Ok(())
+}
+
+/// Test invalid var and invalid values
+#[test]
+fn config_invalid() {
+    // -- CHECK - var not parsable
+    let mut config = Config::default();
+    let result = config.override_with(|name| (name == "WEB_PORT").then(|| "http".to_string()));
+    match result {
+        Err(Error::VarInvalid(name, value)) => {
+            assert_eq!("WEB_PORT", name);
+            assert_eq!("http", value);
+        }
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    // -- CHECK - invalid values
+    let mut config = Config::default();
+    config.db.url = "mysql://localhost/app".to_string();
+    assert!(matches!(
+        config.validate(),
+        Err(Error::Invalid("db.url", _))
+    ));
+
+    let mut config = Config::default();
+    config.mail.smtp_user = Some("user".to_string());
+    assert!(matches!(
+        config.validate(),
+        Err(Error::Invalid("mail.smtp_user", _))
+    ));
+
+    let mut config = Config::default();
+    config.log.level = "info,sqlx=loud".to_string();
+    assert!(matches!(
+        config.validate(),
+        Err(Error::Invalid("log.level", _))
+    ));
+
+    let mut config = Config::default();
+    config.security.token_key = Some("too-short".to_string());
+    assert!(matches!(
+        config.validate(),
+        Err(Error::Invalid("security.token_key", _))
+    ));
+}
@@ -0,0 +1,117 @@
+use super::{render_shopping_list, Mailer};
+use crate::model::{Grocery, GroceryStatus, GrocerySummary, Quantity, ShoppingList};
+use rust_decimal::Decimal;
+use std::fs;
+
+/// Test render of a shopping list (text and html bodies)
+#[test]
+fn mail_render_shopping_list() {
+    // -- FIXTURE
+    let shopping_list = ShoppingList {
+        id: 10,
+        cid: 123,
+        name: "weekly <special>".to_string(),
+    };
+    let groceries = vec![
+        new_grocery(100, "banana", 25, GroceryStatus::Basket),
+        Grocery {
+            quantity: Quantity(Decimal::new(2500, 3)),
+            unit: Some("kg".to_string()),
+            ..new_grocery(101, "fish & chips", 20, GroceryStatus::Shelf)
+        },
+    ];
+    let summary = GrocerySummary {
+        total_cost: 75,
+        basket_cost: 25,
+        shelf_cost: 50,
+        total_count: 2,
+        basket_count: 1,
+        shelf_count: 1,
+    };
+
+    // -- ACTION
+    let (subject, text, html) = render_shopping_list(&shopping_list, &groceries, &summary);
+
+    // -- CHECK
+    assert_eq!("Shopping list - weekly <special>", subject);
+    assert!(text.contains("[x] banana - 1 x 25"));
+    assert!(text.contains("[ ] fish & chips - 2.5 kg x 20"));
+    assert!(text.contains("Total: 75 (2 items)"));
+    assert!(html.contains("<h1>weekly &lt;special&gt;</h1>"));
+    assert!(html.contains("<td>fish &amp; chips</td><td>2.5 kg</td><td>20</td>"));
+    assert!(html.contains("Total: 75 (2 items)"));
+}
+
+/// Test send to the file transport
+#[tokio::test]
+async fn mail_send_file_ok() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let dir = std::env::temp_dir().join("shopping-list-mail_send_file_ok");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+    let mailer = Mailer::new_file(dir.clone(), "Shopping List <noreply@localhost>")?;
+
+    // -- ACTION
+    mailer
+        .send(
+            "demo@localhost",
+            "test - mail_send_file_ok",
+            "text body".to_string(),
+            "<p>html body</p>".to_string(),
+        )
+        .await?;
+
+    // -- CHECK
+    let files = fs::read_dir(&dir)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(1, files.len(), "number of emails");
+    let eml = fs::read_to_string(files[0].path())?;
+    assert!(eml.contains("To: demo@localhost"));
+    assert!(eml.contains("Subject: test - mail_send_file_ok"));
+    assert!(eml.contains("text body"));
+    assert!(eml.contains("<p>html body</p>"));
+
+    Ok(())
+}
+
+/// Test send with an invalid recipient
+#[tokio::test]
+async fn mail_send_invalid_address() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let mailer = Mailer::new_file(std::env::temp_dir(), "Shopping List <noreply@localhost>")?;
+
+    // -- ACTION
+    let result = mailer
+        .send(
+            "not an email",
+            "subject",
+            "text".to_string(),
+            "html".to_string(),
+        )
+        .await;
+
+    // -- CHECK
+    match result {
+        Ok(_) => panic!("Should not send to an invalid address"),
+        Err(super::Error::InvalidAddress(address)) => assert_eq!("not an email", address),
+        other => panic!("Wrong Error {:?} ", other),
+    }
+
+    Ok(())
+}
+
+// region:    Test Utils
+fn new_grocery(id: i64, name: &str, cost: i64, status: GroceryStatus) -> Grocery {
+    Grocery {
+        id,
+        cid: 123,
+        list_id: Some(10),
+        position: 0,
+        quantity: Quantity::default(),
+        unit: None,
+        cost,
+        name: name.to_string(),
+        status,
+        category_id: None,
+    }
+}
+// endregion: Test Utils
@@ -0,0 +1,217 @@
+use super::{Budget, BudgetLevel, BudgetMac, BudgetRow, BudgetScope};
+use crate::config::Config;
+use crate::model;
+use crate::model::db::init_db;
+use crate::model::grocery::{GroceryMac, GroceryPatch};
+use crate::security::UserCtx;
+use std::time::Duration;
+
+/// Test shopping list budget levels
+#[tokio::test]
+async fn model_budget_list_levels() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE (list 10 total is 75)
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- CHECK - no limit
+    assert!(BudgetMac::get_for_list(&db, &utx, 10).await?.is_none());
+
+    // -- CHECK - levels
+    for (limit, level, overshoot) in [
+        (100, BudgetLevel::Under, 0),
+        (80, BudgetLevel::Near, 0),
+        (70, BudgetLevel::Over, 5),
+    ] {
+        let budget = Budget {
+            limit: Some(limit),
+            strict: false,
+        };
+        let status = BudgetMac::set_for_list(&db, &utx, 10, budget)
+            .await?
+            .expect("should have a budget status");
+        assert_eq!(limit, status.limit);
+        assert_eq!(75, status.total);
+        assert_eq!(level, status.level);
+        assert_eq!(overshoot, status.overshoot);
+    }
+
+    // -- CHECK - clear the limit
+    let status = BudgetMac::set_for_list(&db, &utx, 10, Budget::default()).await?;
+    assert!(status.is_none());
+
+    Ok(())
+}
+
+/// Test user budget (groceries without list)
+#[tokio::test]
+async fn model_budget_user() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let budget = Budget {
+        limit: Some(10),
+        strict: false,
+    };
+    BudgetMac::set_for_user(&db, &utx, budget).await?;
+    let data_fx = GroceryPatch {
+        name: Some("test - model_budget_user".to_string()),
+        cost: Some(12),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let grocery = GroceryMac::create(&db, &utx, data_fx).await?;
+    let grocery = BudgetMac::with_status(&db, grocery).await?;
+
+    // -- CHECK
+    let status = grocery.budget.expect("should have a budget status");
+    assert_eq!(12, status.total, "list groceries should not count");
+    assert_eq!(BudgetLevel::Over, status.level);
+    assert_eq!(2, status.overshoot);
+
+    Ok(())
+}
+
+/// Test strict budget rejects writes going over the limit
+#[tokio::test]
+async fn model_budget_strict() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE (list 10 total is 75)
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let budget = Budget {
+        limit: Some(80),
+        strict: true,
+    };
+    BudgetMac::set_for_list(&db, &utx, 10, budget).await?;
+    let data_fx = GroceryPatch {
+        list_id: Some(10),
+        name: Some("test - model_budget_strict".to_string()),
+        cost: Some(10),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let create_result = GroceryMac::create(&db, &utx, data_fx).await;
+    let update_result = GroceryMac::update(
+        &db,
+        &utx,
+        100,
+        GroceryPatch {
+            cost: Some(40),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    // -- CHECK
+    assert!(matches!(
+        create_result,
+        Err(model::Error::BudgetExceeded(80, 85))
+    ));
+    assert!(matches!(
+        update_result,
+        Err(model::Error::BudgetExceeded(80, 90))
+    ));
+    assert_eq!(2, GroceryMac::list_by_list_id(&db, &utx, 10).await?.len());
+    assert_eq!(25, GroceryMac::get(&db, &utx, 100).await?.cost);
+
+    // -- CHECK - a lower cost is still accepted
+    let grocery = GroceryMac::update(
+        &db,
+        &utx,
+        100,
+        GroceryPatch {
+            cost: Some(5),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(5, grocery.cost);
+
+    Ok(())
+}
+
+/// Test the strict budget total locks its scope (a concurrent write of the scope waits)
+#[tokio::test]
+async fn model_budget_total_locks_scope() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE (list 10 total is 75)
+    let db = init_db(&Config::load()?.db).await?;
+    let mut tx = db.begin().await?;
+    let total_before = BudgetMac::total(&mut tx, &BudgetScope::List(10)).await?;
+
+    // -- ACTION - concurrent write transaction of the same scope
+    let db_2 = db.clone();
+    let concurrent = tokio::spawn(async move {
+        let mut tx = db_2.begin().await?;
+        BudgetMac::total(&mut tx, &BudgetScope::List(10)).await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // -- CHECK - it waits for the first transaction, then sees its write
+    assert!(!concurrent.is_finished(), "should wait for the lock");
+    sqlx::query("UPDATE groceries SET cost = cost + 5 WHERE id = 100")
+        .execute(&mut tx)
+        .await?;
+    tx.commit().await?;
+    assert_eq!(75, total_before);
+    assert_eq!(80, concurrent.await??);
+
+    Ok(())
+}
+
+/// Test invalid budget limits
+#[tokio::test]
+async fn model_budget_set_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- CHECK
+    for limit in [-1, i64::MAX] {
+        let budget = Budget {
+            limit: Some(limit),
+            strict: false,
+        };
+        match BudgetMac::set_for_user(&db, &utx, budget).await {
+            Err(model::Error::Validation(errors)) => assert_eq!("limit", errors[0].field),
+            other => panic!("Wrong Result {:?} ", other),
+        }
+    }
+    assert!(BudgetMac::get_for_user(&db, &utx).await?.is_none());
+
+    Ok(())
+}
+
+/// Test the budget level of the large limits (no overflow)
+#[test]
+fn model_budget_level_large_limit() {
+    let row = BudgetRow {
+        budget: Some(i64::MAX),
+        budget_strict: false,
+        total: i64::MAX - 1,
+    };
+
+    let status = row.status().expect("should have a budget status");
+
+    assert_eq!(BudgetLevel::Near, status.level);
+    assert_eq!(0, status.overshoot);
+}
+
+/// Test only the owner can set a shopping list budget
+#[tokio::test]
+async fn model_budget_set_not_owner() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 124 };
+
+    // -- ACTION
+    let result = BudgetMac::set_for_list(&db, &utx, 10, Budget::default()).await;
+
+    // -- CHECK
+    assert!(matches!(
+        result,
+        Err(model::Error::EntityNotFound("shopping_lists", _))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use super::{CategoryMac, CategoryPatch};
+use crate::config::Config;
+use crate::model;
+use crate::model::db::init_db;
+use crate::model::grocery::{GroceryMac, GroceryPatch};
+use crate::security::UserCtx;
+
+/// Test category create
+#[tokio::test]
+async fn model_category_create() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let data_fx = CategoryPatch {
+        name: Some(" bakery ".to_string()),
+        position: Some(2),
+        aisle: Some("B2".to_string()),
+    };
+
+    // -- ACTION
+    let category = CategoryMac::create(&db, &utx, data_fx).await?;
+
+    // -- CHECK
+    assert!(category.id >= 1000, "Id should be >= 1000");
+    assert_eq!(123, category.cid);
+    assert_eq!("bakery", category.name);
+    assert_eq!(2, category.position);
+    assert_eq!(Some("B2".to_string()), category.aisle);
+
+    Ok(())
+}
+
+/// Test category create with a duplicate or an invalid name
+#[tokio::test]
+async fn model_category_create_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- CHECK - duplicate name
+    let data_fx = CategoryPatch {
+        name: Some("produce".to_string()),
+        ..Default::default()
+    };
+    match CategoryMac::create(&db, &utx, data_fx).await {
+        Err(model::Error::Sqlx(sqlx::Error::Database(ex))) => {
+            assert_eq!(Some("23505"), ex.code().as_deref())
+        }
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    // -- CHECK - missing name
+    match CategoryMac::create(&db, &utx, CategoryPatch::default()).await {
+        Err(model::Error::Validation(errors)) => assert_eq!("name", errors[0].field),
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    Ok(())
+}
+
+/// Test category update without any field
+#[tokio::test]
+async fn model_category_update_empty() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- ACTION
+    let result = CategoryMac::update(&db, &utx, 20, CategoryPatch::default()).await;
+
+    // -- CHECK
+    match result {
+        Err(model::Error::Validation(errors)) => assert_eq!("patch", errors[0].field),
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    Ok(())
+}
+
+/// Test category list (own categories, in the aisles order)
+#[tokio::test]
+async fn model_category_list() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let data_fx = CategoryPatch {
+        name: Some("frozen".to_string()),
+        position: Some(-1),
+        ..Default::default()
+    };
+    CategoryMac::create(&db, &utx, data_fx).await?;
+
+    // -- ACTION
+    let categories = CategoryMac::list(&db, &utx).await?;
+
+    // -- CHECK
+    let names: Vec<&str> = categories.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(vec!["frozen", "produce", "dairy"], names);
+
+    // -- CHECK - other user
+    let categories = CategoryMac::list(&db, &UserCtx { user_id: 124 }).await?;
+    assert_eq!(0, categories.len());
+
+    Ok(())
+}
+
+/// Test category update and delete of another user
+#[tokio::test]
+async fn model_category_other_user() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 124 };
+    let data_fx = CategoryPatch {
+        name: Some("renamed".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let update_result = CategoryMac::update(&db, &utx, 20, data_fx).await;
+    let delete_result = CategoryMac::delete(&db, &utx, 20).await;
+
+    // -- CHECK
+    for result in [update_result, delete_result] {
+        match result {
+            Err(model::Error::EntityNotFound(typ, id)) => {
+                assert_eq!("categories", typ);
+                assert_eq!("20", id);
+            }
+            other => panic!("Wrong Result {:?} ", other),
+        }
+    }
+
+    Ok(())
+}
+
+/// Test category delete (its groceries become uncategorized)
+#[tokio::test]
+async fn model_category_delete() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let data_fx = GroceryPatch {
+        category_id: Some(20),
+        ..Default::default()
+    };
+    GroceryMac::update(&db, &utx, 100, data_fx).await?;
+
+    // -- ACTION
+    let category = CategoryMac::delete(&db, &utx, 20).await?;
+
+    // -- CHECK
+    assert_eq!("produce", category.name);
+    let grocery = GroceryMac::get(&db, &utx, 100).await?;
+    assert_eq!(None, grocery.category_id);
+
+    Ok(())
+}
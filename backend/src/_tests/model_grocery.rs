@@ -1,15 +1,20 @@
 use super::GroceryMac;
+use crate::config::{Config, DbConfig};
 use crate::model;
 use crate::model::db::init_db;
-use crate::model::grocery::{Grocery, GroceryPatch, GroceryStatus};
-use crate::security::utx_from_token;
+use crate::model::grocery::{
+    Grocery, GroceryListOptions, GroceryOp, GroceryOpKind, GroceryPatch, GrocerySort,
+    GroceryStatus, Quantity,
+};
+use crate::security::UserCtx;
+use rust_decimal::Decimal;
 
 /// Test grocery create
 #[tokio::test]
 async fn model_grocery_create() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
-    let db = init_db().await?;
-    let utx = utx_from_token(&db, "123").await?;
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
     let data_fx = GroceryPatch {
         name: Some("test - model_grocery_create 1".to_string()),
         cost: Some(0),
@@ -22,6 +27,7 @@ async fn model_grocery_create() -> Result<(), Box<dyn std::error::Error>> {
     // -- CHECK
     assert!(grocery_created.id >= 1000, "Id should be >= 1000");
     assert_eq!(data_fx.name.unwrap(), grocery_created.name);
+    assert_eq!(123, grocery_created.cid);
     assert_eq!(GroceryStatus::Shelf, grocery_created.status);
 
     Ok(())
@@ -31,8 +37,8 @@ async fn model_grocery_create() -> Result<(), Box<dyn std::error::Error>> {
 #[tokio::test]
 async fn model_grocery_get_ok() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
-    let db = init_db().await?;
-    let utx = utx_from_token(&db, "123").await?;
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
 
     // -- ACTION
     let grocery = GroceryMac::get(&db, &utx, 100).await?;
@@ -49,8 +55,8 @@ async fn model_grocery_get_ok() -> Result<(), Box<dyn std::error::Error>> {
 #[tokio::test]
 async fn model_grocery_get_wrong_id() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
-    let db = init_db().await?;
-    let utx = utx_from_token(&db, "123").await?;
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
 
     // -- ACTION
     let result = GroceryMac::get(&db, &utx, 999).await;
@@ -72,11 +78,11 @@ async fn model_grocery_get_wrong_id() -> Result<(), Box<dyn std::error::Error>>
 #[tokio::test]
 async fn model_grocery_list() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
-    let db = init_db().await?;
-    let utx = utx_from_token(&db, "123").await?;
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
 
     // -- ACTION
-    let groceries = GroceryMac::list(&db, &utx).await?;
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
 
     // -- CHECK
     assert_eq!(2, groceries.len());
@@ -101,8 +107,8 @@ async fn model_grocery_list() -> Result<(), Box<dyn std::error::Error>> {
 #[tokio::test]
 async fn model_grocery_update_ok() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
-    let db = init_db().await?;
-    let utx = utx_from_token(&db, "123").await?;
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
     let data_fx = GroceryPatch {
         name: Some("test - model_grocery_update_ok 1".to_string()),
         cost: Some(100),
@@ -120,7 +126,7 @@ async fn model_grocery_update_ok() -> Result<(), Box<dyn std::error::Error>> {
         GroceryMac::update(&db, &utx, grocery_fx.id, update_data_fx.clone()).await?;
 
     // -- CHECK
-    let groceries = GroceryMac::list(&db, &utx).await?;
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
     assert_eq!(3, groceries.len());
     assert_eq!(grocery_fx.id, grocery_updated.id);
     assert_eq!(update_data_fx.name.unwrap(), grocery_updated.name);
@@ -132,8 +138,8 @@ async fn model_grocery_update_ok() -> Result<(), Box<dyn std::error::Error>> {
 #[tokio::test]
 async fn model_grocery_delete_simple() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
-    let db = init_db().await?;
-    let utx = utx_from_token(&db, "123").await?;
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
 
     // -- ACTION
     let grocery = GroceryMac::delete(&db, &utx, 100).await?;
@@ -148,3 +154,730 @@ async fn model_grocery_delete_simple() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+/// Test grocery concurrent deletes (one deletes, the other does not find it)
+#[tokio::test]
+async fn model_grocery_delete_concurrent() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- ACTION
+    let (result_1, result_2) = tokio::join!(
+        GroceryMac::delete(&db, &utx, 100),
+        GroceryMac::delete(&db, &utx, 100)
+    );
+
+    // -- CHECK
+    let (deleted, other) = match result_1 {
+        Ok(grocery) => (grocery, result_2),
+        Err(_) => (result_2?, result_1),
+    };
+    assert_eq!(100, deleted.id);
+    assert!(matches!(other, Err(model::Error::EntityNotFound(_, _))));
+
+    Ok(())
+}
+
+/// Test grocery list is scoped to the user
+#[tokio::test]
+async fn model_grocery_list_other_user() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_123 = UserCtx { user_id: 123 };
+    let utx_124 = UserCtx { user_id: 124 };
+    let data_fx = GroceryPatch {
+        name: Some("test - model_grocery_list_other_user".to_string()),
+        ..Default::default()
+    };
+    let grocery_124 = GroceryMac::create(&db, &utx_124, data_fx).await?;
+
+    // -- ACTION
+    let groceries_123 = GroceryMac::list(&db, &utx_123, &GroceryListOptions::default()).await?;
+    let groceries_124 = GroceryMac::list(&db, &utx_124, &GroceryListOptions::default()).await?;
+
+    // -- CHECK
+    assert_eq!(2, groceries_123.len());
+    assert!(groceries_123.iter().all(|g| g.cid == 123));
+    assert_eq!(1, groceries_124.len());
+    assert_eq!(grocery_124.id, groceries_124[0].id);
+    assert_eq!(124, groceries_124[0].cid);
+
+    Ok(())
+}
+
+/// Test grocery get, update and delete of another user's grocery
+#[tokio::test]
+async fn model_grocery_other_user_not_found() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_124 = UserCtx { user_id: 124 };
+    let patch_fx = GroceryPatch {
+        name: Some("test - model_grocery_other_user_not_found".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION (grocery 100 belongs to user 123)
+    let get_result = GroceryMac::get(&db, &utx_124, 100).await;
+    let update_result = GroceryMac::update(&db, &utx_124, 100, patch_fx).await;
+    let delete_result = GroceryMac::delete(&db, &utx_124, 100).await;
+
+    // -- CHECK
+    for result in [get_result, update_result, delete_result] {
+        match result {
+            Ok(_) => panic!("Should not succeed"),
+            Err(model::Error::EntityNotFound(typ, id)) => {
+                assert_eq!("groceries", typ);
+                assert_eq!(100.to_string(), id);
+            }
+            other_error => panic!("Wrong Error {:?} ", other_error),
+        }
+    }
+
+    // -- CHECK - grocery 100 untouched
+    let grocery = GroceryMac::get(&db, &UserCtx { user_id: 123 }, 100).await?;
+    assert_eq!("banana", grocery.name);
+
+    Ok(())
+}
+
+/// Test grocery reorder
+#[tokio::test]
+async fn model_grocery_reorder() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let data_fx = GroceryPatch {
+        name: Some("test - model_grocery_reorder".to_string()),
+        ..Default::default()
+    };
+    let grocery_fx = GroceryMac::create(&db, &utx, data_fx).await?;
+
+    // -- ACTION (999 does not exist and is skipped)
+    GroceryMac::reorder(&db, &utx, vec![100, 999, grocery_fx.id, 101]).await?;
+
+    // -- CHECK
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    let ids: Vec<i64> = groceries.iter().map(|g| g.id).collect();
+    assert_eq!(vec![100, grocery_fx.id, 101], ids);
+
+    // -- CHECK - a new grocery still comes first
+    let data_fx = GroceryPatch {
+        name: Some("test - model_grocery_reorder 2".to_string()),
+        ..Default::default()
+    };
+    let grocery_fx_2 = GroceryMac::create(&db, &utx, data_fx).await?;
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    assert_eq!(grocery_fx_2.id, groceries[0].id);
+    assert_eq!(100, groceries[1].id);
+
+    Ok(())
+}
+
+/// Test grocery reorder of another user's grocery
+#[tokio::test]
+async fn model_grocery_reorder_other_user() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 124 };
+
+    // -- ACTION
+    let result = GroceryMac::reorder(&db, &utx, vec![100, 101]).await;
+
+    // -- CHECK
+    assert!(matches!(
+        result,
+        Err(model::Error::EntityNotFound("groceries", _))
+    ));
+    let groceries = GroceryMac::list(
+        &db,
+        &UserCtx { user_id: 123 },
+        &GroceryListOptions::default(),
+    )
+    .await?;
+    assert_eq!(101, groceries[0].id, "order should be unchanged");
+
+    Ok(())
+}
+
+/// Test grocery summary
+#[tokio::test]
+async fn model_grocery_summary() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- ACTION
+    let summary = GroceryMac::summary(&db, &utx, None).await?;
+    let list_summary = GroceryMac::summary(&db, &utx, Some(10)).await?;
+
+    // -- CHECK (banana 25 in basket, orange 50 on shelf)
+    for summary in [summary, list_summary] {
+        assert_eq!(75, summary.total_cost);
+        assert_eq!(25, summary.basket_cost);
+        assert_eq!(50, summary.shelf_cost);
+        assert_eq!(2, summary.total_count);
+        assert_eq!(1, summary.basket_count);
+        assert_eq!(1, summary.shelf_count);
+    }
+
+    // -- CHECK - other user
+    let summary = GroceryMac::summary(&db, &UserCtx { user_id: 124 }, None).await?;
+    assert_eq!(0, summary.total_cost);
+    assert_eq!(0, summary.total_count);
+
+    Ok(())
+}
+
+/// Test grocery quantity and unit (summary total is quantity x unit price)
+#[tokio::test]
+async fn model_grocery_quantity_summary() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let data_fx = GroceryPatch {
+        list_id: Some(10),
+        name: Some("test - model_grocery_quantity_summary".to_string()),
+        quantity: Some(Quantity(Decimal::new(15, 1))),
+        unit: Some("kg".to_string()),
+        cost: Some(30),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let grocery = GroceryMac::create(&db, &utx, data_fx).await?;
+    let summary = GroceryMac::summary(&db, &utx, Some(10)).await?;
+
+    // -- CHECK
+    assert_eq!(Quantity(Decimal::new(15, 1)), grocery.quantity);
+    assert_eq!(Some("kg".to_string()), grocery.unit);
+    assert_eq!(30, grocery.cost);
+    // 75 + 1.5 x 30
+    assert_eq!(120, summary.total_cost);
+    assert_eq!(95, summary.shelf_cost);
+    assert_eq!(3, summary.total_count);
+
+    Ok(())
+}
+
+/// Test grocery create validation (all field errors, trimmed name)
+#[tokio::test]
+async fn model_grocery_create_validation() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let invalid_fx = GroceryPatch {
+        cost: Some(-1),
+        quantity: Some(Quantity(Decimal::ZERO)),
+        unit: Some("x".repeat(33)),
+        ..Default::default()
+    };
+
+    // -- ACTION - invalid
+    let result = GroceryMac::create(&db, &utx, invalid_fx).await;
+
+    // -- CHECK - invalid
+    match result {
+        Ok(_) => panic!("Should not create an invalid grocery"),
+        Err(model::Error::Validation(errors)) => {
+            let fields: Vec<&str> = errors.iter().map(|e| e.field).collect();
+            assert_eq!(vec!["name", "cost", "quantity", "unit"], fields);
+            assert_eq!("is required", errors[0].message);
+        }
+        other_error => panic!("Wrong Error {:?} ", other_error),
+    }
+
+    // -- ACTION - trimmed
+    let data_fx = GroceryPatch {
+        name: Some("  test - model_grocery_create_validation  ".to_string()),
+        unit: Some(" kg ".to_string()),
+        ..Default::default()
+    };
+    let grocery = GroceryMac::create(&db, &utx, data_fx).await?;
+
+    // -- CHECK - trimmed
+    assert_eq!("test - model_grocery_create_validation", grocery.name);
+    assert_eq!(Some("kg".to_string()), grocery.unit);
+
+    Ok(())
+}
+
+/// Test grocery update validation (name optional, but not empty, and one field at least)
+#[tokio::test]
+async fn model_grocery_update_validation() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- ACTION - valid without name
+    let data_fx = GroceryPatch {
+        cost: Some(30),
+        ..Default::default()
+    };
+    let grocery = GroceryMac::update(&db, &utx, 100, data_fx).await?;
+    assert_eq!("banana", grocery.name);
+
+    // -- ACTION - invalid
+    for (data_fx, field, message) in [
+        (
+            GroceryPatch {
+                name: Some("   ".to_string()),
+                ..Default::default()
+            },
+            "name",
+            "cannot be empty",
+        ),
+        (
+            GroceryPatch {
+                name: Some("x".repeat(201)),
+                ..Default::default()
+            },
+            "name",
+            "cannot be longer than 200 characters",
+        ),
+        (
+            GroceryPatch {
+                quantity: Some(Quantity(Decimal::new(12345, 4))),
+                ..Default::default()
+            },
+            "quantity",
+            "cannot have more than 3 decimals",
+        ),
+        (GroceryPatch::default(), "patch", "has no field to update"),
+    ] {
+        match GroceryMac::update(&db, &utx, 100, data_fx).await {
+            Err(model::Error::Validation(errors)) => {
+                assert_eq!(1, errors.len());
+                assert_eq!(field, errors[0].field);
+                assert_eq!(message, errors[0].message);
+            }
+            other => panic!("Wrong Result {:?} ", other),
+        }
+    }
+
+    Ok(())
+}
+
+/// Test grocery list grouped by category, in the aisles order (uncategorized last)
+#[tokio::test]
+async fn model_grocery_list_grouped() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    for (id, category_id) in [(100, 21), (101, 20)] {
+        let data_fx = GroceryPatch {
+            category_id: Some(category_id),
+            ..Default::default()
+        };
+        GroceryMac::update(&db, &utx, id, data_fx).await?;
+    }
+    let data_fx = GroceryPatch {
+        name: Some("test - model_grocery_list_grouped".to_string()),
+        ..Default::default()
+    };
+    GroceryMac::create(&db, &utx, data_fx).await?;
+
+    // -- ACTION
+    let groups = GroceryMac::list_grouped(&db, &utx).await?;
+
+    // -- CHECK
+    let groups: Vec<(Option<&str>, Vec<&str>)> = groups
+        .iter()
+        .map(|group| {
+            (
+                group.category.as_ref().map(|c| c.name.as_str()),
+                group.groceries.iter().map(|g| g.name.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (Some("produce"), vec!["orange"]),
+            (Some("dairy"), vec!["banana"]),
+            (None, vec!["test - model_grocery_list_grouped"]),
+        ],
+        groups
+    );
+
+    Ok(())
+}
+
+/// Test grocery create with a category of another user
+#[tokio::test]
+async fn model_grocery_create_other_category() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 124 };
+    let data_fx = GroceryPatch {
+        name: Some("test - model_grocery_create_other_category".to_string()),
+        category_id: Some(20),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = GroceryMac::create(&db, &utx, data_fx).await;
+
+    // -- CHECK
+    match result {
+        Err(model::Error::EntityNotFound(typ, id)) => {
+            assert_eq!("categories", typ);
+            assert_eq!("20", id);
+        }
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    Ok(())
+}
+
+/// Test grocery list filters
+#[tokio::test]
+async fn model_grocery_list_filters() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    for (name, cost) in [("rice 100%", 30), ("rice_basmati", 40)] {
+        let data_fx = GroceryPatch {
+            name: Some(name.to_string()),
+            cost: Some(cost),
+            ..Default::default()
+        };
+        GroceryMac::create(&db, &utx, data_fx).await?;
+    }
+
+    // -- ACTION / CHECK - status
+    let options = GroceryListOptions {
+        status: Some(GroceryStatus::Basket),
+        ..Default::default()
+    };
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    assert_eq!(vec!["banana"], names(&groceries));
+
+    // -- ACTION / CHECK - name contains, case insensitive, with the wildcards escaped
+    let options = GroceryListOptions {
+        name: Some(" RICE ".to_string()),
+        sort: GrocerySort::Name,
+        ..Default::default()
+    };
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    assert_eq!(vec!["rice 100%", "rice_basmati"], names(&groceries));
+
+    let options = GroceryListOptions {
+        name: Some("0%".to_string()),
+        ..Default::default()
+    };
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    assert_eq!(vec!["rice 100%"], names(&groceries));
+
+    // -- ACTION / CHECK - cost range
+    let options = GroceryListOptions {
+        cost_min: Some(30),
+        cost_max: Some(45),
+        sort: GrocerySort::Cost,
+        ..Default::default()
+    };
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    assert_eq!(vec!["rice 100%", "rice_basmati"], names(&groceries));
+
+    // -- ACTION / CHECK - created (newest first)
+    let options = GroceryListOptions {
+        sort: GrocerySort::Created,
+        ..Default::default()
+    };
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    assert_eq!(
+        vec!["rice_basmati", "rice 100%", "orange", "banana"],
+        names(&groceries)
+    );
+
+    Ok(())
+}
+
+/// Test grocery list pages (following the next cursor)
+#[tokio::test]
+async fn model_grocery_list_pages() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    for name in ["apple", "banana"] {
+        let data_fx = GroceryPatch {
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        GroceryMac::create(&db, &utx, data_fx).await?;
+    }
+    let mut options = GroceryListOptions {
+        sort: GrocerySort::Name,
+        limit: Some(2),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let mut pages = Vec::new();
+    loop {
+        let groceries = GroceryMac::list(&db, &utx, &options).await?;
+        pages.push(names(&groceries).join(","));
+        match options.next_cursor(&groceries) {
+            Some(next) => options.cursor = Some(next),
+            None => break,
+        }
+    }
+
+    // -- CHECK - the two bananas are ordered by id (newest first)
+    assert_eq!(vec!["apple,banana", "banana,orange", ""], pages);
+
+    Ok(())
+}
+
+/// Test grocery list with invalid options
+#[tokio::test]
+async fn model_grocery_list_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let options = GroceryListOptions {
+        sort: GrocerySort::Name,
+        limit: Some(1),
+        ..Default::default()
+    };
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    let cursor = options.next_cursor(&groceries);
+
+    for (options, field) in [
+        (
+            GroceryListOptions {
+                limit: Some(0),
+                ..Default::default()
+            },
+            "limit",
+        ),
+        (
+            GroceryListOptions {
+                cost_min: Some(10),
+                cost_max: Some(5),
+                ..Default::default()
+            },
+            "cost_min",
+        ),
+        (
+            GroceryListOptions {
+                cursor: Some("not-a-cursor".to_string()),
+                ..Default::default()
+            },
+            "cursor",
+        ),
+        (
+            // cursor of another sort
+            GroceryListOptions {
+                sort: GrocerySort::Cost,
+                cursor,
+                ..Default::default()
+            },
+            "cursor",
+        ),
+    ] {
+        // -- ACTION
+        let result = GroceryMac::list(&db, &utx, &options).await;
+
+        // -- CHECK
+        match result {
+            Err(model::Error::Validation(errors)) => assert_eq!(field, errors[0].field),
+            other => panic!("Wrong Result {:?} ", other),
+        }
+    }
+
+    Ok(())
+}
+
+/// Test grocery search (full-text and fuzzy), scoped to the user groceries
+#[tokio::test]
+async fn model_grocery_search() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    for name in ["greek yoghurt", "oat milk", "red apple"] {
+        let data_fx = GroceryPatch {
+            list_id: Some(10),
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        GroceryMac::create(&db, &utx, data_fx).await?;
+    }
+
+    // -- ACTION / CHECK - spelling variant
+    let groceries = GroceryMac::search(&db, &utx, "yogurt", None).await?;
+    assert_eq!(vec!["greek yoghurt"], names(&groceries));
+
+    // -- ACTION / CHECK - stemmed words
+    let groceries = GroceryMac::search(&db, &utx, " apples ", None).await?;
+    assert_eq!(vec!["red apple"], names(&groceries));
+
+    // -- ACTION / CHECK - typo, best match first
+    let groceries = GroceryMac::search(&db, &utx, "oranje", None).await?;
+    assert_eq!(Some(&"orange"), names(&groceries).first());
+
+    // -- ACTION / CHECK - other user (not a member of the list)
+    let groceries = GroceryMac::search(&db, &UserCtx { user_id: 124 }, "yogurt", None).await?;
+    assert_eq!(0, groceries.len());
+
+    // -- ACTION / CHECK - invalid
+    match GroceryMac::search(&db, &utx, "  ", Some(0)).await {
+        Err(model::Error::Validation(errors)) => {
+            let fields: Vec<&str> = errors.iter().map(|e| e.field).collect();
+            assert_eq!(vec!["q", "limit"], fields);
+        }
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    Ok(())
+}
+
+/// Test grocery batch (create, update and delete in order)
+#[tokio::test]
+async fn model_grocery_batch() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let ops_fx = vec![
+        GroceryOp::Create {
+            data: GroceryPatch {
+                name: Some("rice".to_string()),
+                ..Default::default()
+            },
+        },
+        GroceryOp::Update {
+            id: 101,
+            data: GroceryPatch {
+                status: Some(GroceryStatus::Basket),
+                ..Default::default()
+            },
+        },
+        GroceryOp::Delete { id: 100 },
+    ];
+
+    // -- ACTION
+    let results = GroceryMac::batch(&db, &utx, ops_fx).await?;
+
+    // -- CHECK - results
+    let ops: Vec<GroceryOpKind> = results.iter().map(|r| r.op).collect();
+    assert_eq!(
+        vec![
+            GroceryOpKind::Create,
+            GroceryOpKind::Update,
+            GroceryOpKind::Delete
+        ],
+        ops
+    );
+    assert_eq!(2, results[2].index);
+    assert_eq!("rice", results[0].grocery.name);
+    assert_eq!(GroceryStatus::Basket, results[1].grocery.status);
+    assert_eq!("banana", results[2].grocery.name);
+
+    // -- CHECK - list
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    let mut names = names(&groceries);
+    names.sort();
+    assert_eq!(vec!["orange", "rice"], names);
+
+    Ok(())
+}
+
+/// Test grocery batch is all or nothing (fails with the index of the failed operation)
+#[tokio::test]
+async fn model_grocery_batch_rollback() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let ops_fx = vec![
+        GroceryOp::Delete { id: 100 },
+        GroceryOp::Create {
+            data: GroceryPatch {
+                name: Some("rice".to_string()),
+                ..Default::default()
+            },
+        },
+        GroceryOp::Update {
+            id: 999,
+            data: GroceryPatch {
+                name: Some("unknown".to_string()),
+                ..Default::default()
+            },
+        },
+    ];
+
+    // -- ACTION
+    let result = GroceryMac::batch(&db, &utx, ops_fx).await;
+
+    // -- CHECK - failed operation
+    match result {
+        Err(model::Error::BatchOp(index, error)) => {
+            assert_eq!(2, index);
+            assert!(
+                matches!(*error, model::Error::EntityNotFound("groceries", ref id) if id == "999")
+            );
+        }
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    // -- CHECK - nothing applied
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    let mut names = names(&groceries);
+    names.sort();
+    assert_eq!(vec!["banana", "orange"], names);
+
+    // -- CHECK - too many operations
+    let ops_fx = vec![GroceryOp::Delete { id: 100 }; 101];
+    match GroceryMac::batch(&db, &utx, ops_fx).await {
+        Err(model::Error::Validation(errors)) => assert_eq!("ops", errors[0].field),
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    Ok(())
+}
+
+/// Test grocery batch checks the permissions on its own connection (a single connection pool)
+#[tokio::test]
+async fn model_grocery_batch_single_connection() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let config = Config::load()?;
+    let db = init_db(&DbConfig {
+        max_connections: 1,
+        ..config.db
+    })
+    .await?;
+    let utx = UserCtx { user_id: 123 };
+    let ops_fx = vec![
+        GroceryOp::Create {
+            data: GroceryPatch {
+                name: Some("rice".to_string()),
+                list_id: Some(10),
+                category_id: Some(20),
+                ..Default::default()
+            },
+        },
+        GroceryOp::Update {
+            id: 101,
+            data: GroceryPatch {
+                list_id: Some(10),
+                category_id: Some(21),
+                ..Default::default()
+            },
+        },
+        GroceryOp::Delete { id: 100 },
+    ];
+
+    // -- ACTION
+    let results = GroceryMac::batch(&db, &utx, ops_fx).await?;
+
+    // -- CHECK
+    assert_eq!(3, results.len());
+    assert_eq!(Some(20), results[0].grocery.category_id);
+    assert_eq!(Some(21), results[1].grocery.category_id);
+
+    Ok(())
+}
+
+// region:    Test Utils
+fn names(groceries: &[Grocery]) -> Vec<&str> {
+    groceries.iter().map(|g| g.name.as_str()).collect()
+}
+// endregion: Test Utils
@@ -0,0 +1,195 @@
+use super::{ListMemberMac, ListRole};
+use crate::config::Config;
+use crate::model;
+use crate::model::db::init_db;
+use crate::model::grocery::{GroceryListOptions, GroceryMac, GroceryPatch};
+use crate::model::shopping_list::{ShoppingListMac, ShoppingListPatch};
+use crate::security::UserCtx;
+
+/// Test member invite and accept
+#[tokio::test]
+async fn model_list_member_invite_accept() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_owner = UserCtx { user_id: 123 };
+    let utx_member = UserCtx { user_id: 124 };
+
+    // -- ACTION - invite
+    let member = ListMemberMac::invite(&db, &utx_owner, 10, 124, ListRole::Editor).await?;
+
+    // -- CHECK - pending member cannot see the list yet
+    assert!(member.pending);
+    assert_eq!(
+        1,
+        ListMemberMac::list_invitations(&db, &utx_member)
+            .await?
+            .len()
+    );
+    assert!(matches!(
+        ShoppingListMac::get(&db, &utx_member, 10).await,
+        Err(model::Error::EntityNotFound("shopping_lists", _))
+    ));
+
+    // -- ACTION - accept
+    let member = ListMemberMac::accept(&db, &utx_member, 10).await?;
+
+    // -- CHECK - member sees the list and its groceries
+    assert!(!member.pending);
+    assert_eq!(ListRole::Editor, member.role);
+    let shopping_lists = ShoppingListMac::list(&db, &utx_member).await?;
+    assert_eq!(1, shopping_lists.len());
+    assert_eq!(10, shopping_lists[0].id);
+    let groceries = GroceryMac::list(&db, &utx_member, &GroceryListOptions::default()).await?;
+    assert_eq!(2, groceries.len());
+    let members = ListMemberMac::list(&db, &utx_member, 10).await?;
+    assert_eq!(2, members.len());
+
+    Ok(())
+}
+
+/// Test only the owner can invite
+#[tokio::test]
+async fn model_list_member_invite_not_owner() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_owner = UserCtx { user_id: 123 };
+    let utx_editor = UserCtx { user_id: 124 };
+    ListMemberMac::invite(&db, &utx_owner, 10, 124, ListRole::Editor).await?;
+    ListMemberMac::accept(&db, &utx_editor, 10).await?;
+
+    // -- ACTION
+    let result = ListMemberMac::remove(&db, &utx_editor, 10, 123).await;
+
+    // -- CHECK
+    assert!(matches!(
+        result,
+        Err(model::Error::UserNotAuthorized("shopping_lists", _))
+    ));
+
+    Ok(())
+}
+
+/// Test invite as owner is refused (a list has a single owner)
+#[tokio::test]
+async fn model_list_member_invite_owner() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_owner = UserCtx { user_id: 123 };
+
+    // -- ACTION
+    let result = ListMemberMac::invite(&db, &utx_owner, 10, 124, ListRole::Owner).await;
+
+    // -- CHECK
+    match result {
+        Err(model::Error::Validation(errors)) => assert_eq!("role", errors[0].field),
+        other => panic!("Wrong Result {:?} ", other),
+    }
+    assert_eq!(1, ListMemberMac::list(&db, &utx_owner, 10).await?.len());
+
+    Ok(())
+}
+
+/// Test editor can update and delete list groceries
+#[tokio::test]
+async fn model_list_member_editor_grocery() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_owner = UserCtx { user_id: 123 };
+    let utx_editor = UserCtx { user_id: 124 };
+    ListMemberMac::invite(&db, &utx_owner, 10, 124, ListRole::Editor).await?;
+    ListMemberMac::accept(&db, &utx_editor, 10).await?;
+    let patch_fx = GroceryPatch {
+        name: Some("test - model_list_member_editor_grocery".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let grocery = GroceryMac::update(&db, &utx_editor, 100, patch_fx.clone()).await?;
+    GroceryMac::delete(&db, &utx_editor, 101).await?;
+
+    // -- CHECK
+    assert_eq!(patch_fx.name.unwrap(), grocery.name);
+    assert_eq!(
+        1,
+        GroceryMac::list_by_list_id(&db, &utx_owner, 10)
+            .await?
+            .len()
+    );
+
+    Ok(())
+}
+
+/// Test viewer can read but not update or delete list groceries
+#[tokio::test]
+async fn model_list_member_viewer_grocery() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_owner = UserCtx { user_id: 123 };
+    let utx_viewer = UserCtx { user_id: 124 };
+    ListMemberMac::invite(&db, &utx_owner, 10, 124, ListRole::Viewer).await?;
+    ListMemberMac::accept(&db, &utx_viewer, 10).await?;
+    let patch_fx = GroceryPatch {
+        name: Some("test - model_list_member_viewer_grocery".to_string()),
+        list_id: Some(10),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let grocery = GroceryMac::get(&db, &utx_viewer, 100).await?;
+    let create_result = GroceryMac::create(&db, &utx_viewer, patch_fx.clone()).await;
+    let update_result = GroceryMac::update(&db, &utx_viewer, 100, patch_fx).await;
+    let delete_result = GroceryMac::delete(&db, &utx_viewer, 100).await;
+    let rename_result = ShoppingListMac::update(
+        &db,
+        &utx_viewer,
+        10,
+        ShoppingListPatch {
+            name: Some("renamed".to_string()),
+        },
+    )
+    .await;
+
+    // -- CHECK
+    assert_eq!("banana", grocery.name);
+    assert!(matches!(
+        create_result,
+        Err(model::Error::UserNotAuthorized("shopping_lists", _))
+    ));
+    assert!(matches!(
+        update_result,
+        Err(model::Error::UserNotAuthorized("groceries", _))
+    ));
+    assert!(matches!(
+        delete_result,
+        Err(model::Error::UserNotAuthorized("groceries", _))
+    ));
+    assert!(matches!(
+        rename_result,
+        Err(model::Error::UserNotAuthorized("shopping_lists", _))
+    ));
+
+    Ok(())
+}
+
+/// Test member leaves a shopping list
+#[tokio::test]
+async fn model_list_member_remove_self() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx_owner = UserCtx { user_id: 123 };
+    let utx_member = UserCtx { user_id: 124 };
+    ListMemberMac::invite(&db, &utx_owner, 10, 124, ListRole::Viewer).await?;
+    ListMemberMac::accept(&db, &utx_member, 10).await?;
+
+    // -- ACTION
+    ListMemberMac::remove(&db, &utx_member, 10, 124).await?;
+
+    // -- CHECK
+    assert_eq!(0, ShoppingListMac::list(&db, &utx_member).await?.len());
+    assert!(matches!(
+        GroceryMac::get(&db, &utx_member, 100).await,
+        Err(model::Error::EntityNotFound("groceries", _))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use super::{migrate, parse_file_name, revert_last, status, MigrationState};
+use crate::config::Config;
+use crate::model;
+use crate::model::db::init_db;
+use std::path::Path;
+
+const SQL_MIGRATIONS_DIR: &str = "sql/migrations/";
+
+/// Test migrate is applied once (data kept on a second migrate)
+#[tokio::test]
+async fn model_migration_migrate_once() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let (count_before,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM schema_migrations")
+        .fetch_one(&db)
+        .await?;
+
+    // -- ACTION
+    migrate(&db, SQL_MIGRATIONS_DIR).await?;
+
+    // -- CHECK
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM schema_migrations")
+        .fetch_one(&db)
+        .await?;
+    assert!(count_before > 0, "migrations should be recorded");
+    assert_eq!(count_before, count, "no migration should be re-applied");
+    let groceries = sqlx::query("SELECT * FROM groceries")
+        .fetch_all(&db)
+        .await?;
+    assert_eq!(2, groceries.len(), "seed groceries should be kept");
+
+    Ok(())
+}
+
+/// Test concurrent migrate runners apply each migration once (e.g. replicas starting together)
+#[tokio::test]
+async fn model_migration_migrate_concurrent() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    while revert_last(&db, SQL_MIGRATIONS_DIR).await?.is_some() {}
+
+    // -- ACTION
+    let (result_1, result_2) = tokio::join!(
+        migrate(&db, SQL_MIGRATIONS_DIR),
+        migrate(&db, SQL_MIGRATIONS_DIR)
+    );
+
+    // -- CHECK
+    result_1?;
+    result_2?;
+    let statuses = status(&db, SQL_MIGRATIONS_DIR).await?;
+    assert!(statuses.iter().all(|s| s.state == MigrationState::Applied));
+
+    Ok(())
+}
+
+/// Test migrate fails when an applied migration was edited
+#[tokio::test]
+async fn model_migration_checksum_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    sqlx::query("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 1")
+        .execute(&db)
+        .await?;
+
+    // -- ACTION
+    let result = migrate(&db, SQL_MIGRATIONS_DIR).await;
+
+    // -- CHECK
+    match result {
+        Ok(_) => panic!("Should not migrate with an edited migration"),
+        Err(model::Error::MigrationChecksum(version, name)) => {
+            assert_eq!(1, version);
+            assert_eq!("create-schema", name);
+        }
+        other_error => panic!("Wrong Error {:?} ", other_error),
+    }
+
+    Ok(())
+}
+
+/// Test migration file names
+#[test]
+fn model_migration_parse_file_name() {
+    let (version, name) = parse_file_name(Path::new("sql/migrations/0012-add-index.sql")).unwrap();
+    assert_eq!(12, version);
+    assert_eq!("add-index", name);
+
+    assert!(parse_file_name(Path::new("sql/migrations/add-index.sql")).is_err());
+    assert!(parse_file_name(Path::new("sql/migrations/0012.sql")).is_err());
+}
+
+/// Test revert of the last migration, status and re-apply
+#[tokio::test]
+async fn model_migration_revert_status() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+
+    // -- ACTION - revert all
+    let mut reverted = Vec::new();
+    while let Some(status) = revert_last(&db, SQL_MIGRATIONS_DIR).await? {
+        reverted.push(status.version);
+    }
+
+    // -- CHECK - all pending
+    assert!(reverted.contains(&1));
+    let statuses = status(&db, SQL_MIGRATIONS_DIR).await?;
+    assert!(statuses.iter().all(|s| s.state == MigrationState::Pending));
+    assert!(sqlx::query("SELECT * FROM groceries")
+        .fetch_all(&db)
+        .await
+        .is_err());
+
+    // -- ACTION - re-apply
+    migrate(&db, SQL_MIGRATIONS_DIR).await?;
+
+    // -- CHECK - all applied
+    let statuses = status(&db, SQL_MIGRATIONS_DIR).await?;
+    assert!(statuses
+        .iter()
+        .all(|s| s.state == MigrationState::Applied && s.applied_at.is_some()));
+
+    Ok(())
+}
+
+/// Test status without the tracking table (all pending, and the table not created)
+#[tokio::test]
+async fn model_migration_status_no_table() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    while revert_last(&db, SQL_MIGRATIONS_DIR).await?.is_some() {}
+    sqlx::query("DROP TABLE schema_migrations")
+        .execute(&db)
+        .await?;
+
+    // -- ACTION
+    let statuses = status(&db, SQL_MIGRATIONS_DIR).await?;
+
+    // -- CHECK
+    assert!(statuses.iter().all(|s| s.state == MigrationState::Pending));
+    let (exists,): (bool,) = sqlx::query_as("SELECT to_regclass('schema_migrations') IS NOT NULL")
+        .fetch_one(&db)
+        .await?;
+    assert!(!exists, "status should not create the tracking table");
+
+    // -- CHECK - migrate creates it again
+    migrate(&db, SQL_MIGRATIONS_DIR).await?;
+    let statuses = status(&db, SQL_MIGRATIONS_DIR).await?;
+    assert!(statuses.iter().all(|s| s.state == MigrationState::Applied));
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use super::{ShoppingListMac, ShoppingListPatch};
+use crate::config::Config;
+use crate::model;
+use crate::model::db::init_db;
+use crate::model::grocery::{GroceryListOptions, GroceryMac, GroceryPatch};
+use crate::security::UserCtx;
+
+/// Test shopping list create
+#[tokio::test]
+async fn model_shopping_list_create() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+    let data_fx = ShoppingListPatch {
+        name: Some("test - model_shopping_list_create".to_string()),
+    };
+
+    // -- ACTION
+    let shopping_list = ShoppingListMac::create(&db, &utx, data_fx.clone()).await?;
+
+    // -- CHECK
+    assert!(shopping_list.id >= 1000, "Id should be >= 1000");
+    assert_eq!(123, shopping_list.cid);
+    assert_eq!(data_fx.name.unwrap(), shopping_list.name);
+
+    Ok(())
+}
+
+/// Test shopping list list
+#[tokio::test]
+async fn model_shopping_list_list() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- ACTION
+    let shopping_lists = ShoppingListMac::list(&db, &utx).await?;
+
+    // -- CHECK
+    assert_eq!(1, shopping_lists.len());
+    assert_eq!(10, shopping_lists[0].id);
+    assert_eq!("weekly", shopping_lists[0].name);
+
+    // -- CHECK - other user
+    let shopping_lists = ShoppingListMac::list(&db, &UserCtx { user_id: 124 }).await?;
+    assert_eq!(0, shopping_lists.len());
+
+    Ok(())
+}
+
+/// Test shopping list get of another user
+#[tokio::test]
+async fn model_shopping_list_get_other_user() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 124 };
+
+    // -- ACTION
+    let result = ShoppingListMac::get(&db, &utx, 10).await;
+
+    // -- CHECK
+    match result {
+        Ok(_) => panic!("Should not succeed"),
+        Err(model::Error::EntityNotFound(typ, id)) => {
+            assert_eq!("shopping_lists", typ);
+            assert_eq!(10.to_string(), id);
+        }
+        other_error => panic!("Wrong Error {:?} ", other_error),
+    }
+
+    Ok(())
+}
+
+/// Test grocery create in another user's shopping list
+#[tokio::test]
+async fn model_shopping_list_grocery_create_other_user() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 124 };
+    let data_fx = GroceryPatch {
+        list_id: Some(10),
+        name: Some("test - model_shopping_list_grocery_create_other_user".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = GroceryMac::create(&db, &utx, data_fx).await;
+
+    // -- CHECK
+    assert!(matches!(
+        result,
+        Err(model::Error::EntityNotFound("shopping_lists", _))
+    ));
+
+    Ok(())
+}
+
+/// Test shopping list delete also deletes its groceries
+#[tokio::test]
+async fn model_shopping_list_delete_cascade() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let utx = UserCtx { user_id: 123 };
+
+    // -- ACTION
+    let shopping_list = ShoppingListMac::delete(&db, &utx, 10).await?;
+
+    // -- CHECK
+    assert_eq!(10, shopping_list.id);
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    assert_eq!(0, groceries.len(), "groceries of list 10 should be deleted");
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use super::{split_sql, SqlStatement};
+
+/// Test split on `;` with statement lines
+#[test]
+fn model_sql_script_split_simple() {
+    let statements = split_sql("SELECT 1;\n\nSELECT 2;\nSELECT\n  3");
+
+    assert_eq!(
+        vec![
+            SqlStatement {
+                sql: "SELECT 1",
+                line: 1
+            },
+            SqlStatement {
+                sql: "SELECT 2",
+                line: 3
+            },
+            SqlStatement {
+                sql: "SELECT\n  3",
+                line: 4
+            },
+        ],
+        statements
+    );
+}
+
+/// Test `;` in strings, quoted identifiers and E strings
+#[test]
+fn model_sql_script_split_quotes() {
+    let statements = split_sql(
+        "INSERT INTO t (name) VALUES ('a;b'), ('it''s;');\n\
+         SELECT \"odd;name\" FROM t;\n\
+         SELECT E'\\';', 'c:\\';",
+    );
+
+    assert_eq!(3, statements.len());
+    assert_eq!(
+        "INSERT INTO t (name) VALUES ('a;b'), ('it''s;')",
+        statements[0].sql
+    );
+    assert_eq!("SELECT \"odd;name\" FROM t", statements[1].sql);
+    assert_eq!("SELECT E'\\';', 'c:\\'", statements[2].sql);
+    assert_eq!(3, statements[2].line);
+}
+
+/// Test statement lines after an escaped line break in an E string
+#[test]
+fn model_sql_script_split_escaped_newline() {
+    let statements = split_sql("SELECT E'a\\\nb;';\nSELECT 2;\n\nSELECT 3;");
+
+    assert_eq!(3, statements.len());
+    assert_eq!("SELECT E'a\\\nb;'", statements[0].sql);
+    assert_eq!(1, statements[0].line);
+    assert_eq!(3, statements[1].line);
+    assert_eq!(5, statements[2].line);
+}
+
+/// Test `;` in dollar-quoted bodies
+#[test]
+fn model_sql_script_split_dollar_quotes() {
+    let script = "CREATE FUNCTION f() RETURNS trigger AS $$\n\
+                  BEGIN\n  NEW.a := 1;\n  RETURN NEW;\nEND;\n$$ LANGUAGE plpgsql;\n\
+                  DO $body$ BEGIN PERFORM 1; END $body$;\n\
+                  SELECT $1;";
+    let statements = split_sql(script);
+
+    assert_eq!(3, statements.len());
+    assert!(statements[0].sql.ends_with("$$ LANGUAGE plpgsql"));
+    assert_eq!("DO $body$ BEGIN PERFORM 1; END $body$", statements[1].sql);
+    assert_eq!(7, statements[1].line);
+    assert_eq!("SELECT $1", statements[2].sql);
+    assert_eq!(8, statements[2].line);
+}
+
+/// Test comments (with `;` and quotes) and comment-only statements
+#[test]
+fn model_sql_script_split_comments() {
+    let script = "-- header; it's a comment\n\
+                  /* block; /* nested; */ 'still' */\n\
+                  SELECT 1; -- trailing;\n\
+                  -- only a comment;\n";
+    let statements = split_sql(script);
+
+    assert_eq!(1, statements.len());
+    assert_eq!(3, statements[0].line);
+    assert!(statements[0].sql.ends_with("SELECT 1"));
+}
@@ -0,0 +1,65 @@
+use super::{UserMac, UserPatch};
+use crate::config::Config;
+use crate::model;
+use crate::model::db::init_db;
+
+/// Test user create
+#[tokio::test]
+async fn model_user_create() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let data_fx = UserPatch {
+        username: Some("test - model_user_create".to_string()),
+        pwd: Some("not-a-real-hash".to_string()),
+    };
+
+    // -- ACTION
+    let user = UserMac::create(&db, data_fx.clone()).await?;
+
+    // -- CHECK
+    assert!(user.id >= 1000, "Id should be >= 1000");
+    assert_eq!(data_fx.username.unwrap(), user.username);
+
+    Ok(())
+}
+
+/// Test user get_for_auth
+#[tokio::test]
+async fn model_user_get_for_auth() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+
+    // -- ACTION
+    let user = UserMac::get_for_auth(&db, "demo").await?;
+
+    // -- CHECK
+    assert_eq!(123, user.id);
+    assert!(
+        user.pwd.starts_with("$argon2"),
+        "pwd should be an argon2 hash"
+    );
+
+    Ok(())
+}
+
+/// Test user get wrong id
+#[tokio::test]
+async fn model_user_get_wrong_id() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+
+    // -- ACTION
+    let result = UserMac::get(&db, 999).await;
+
+    // -- CHECK
+    match result {
+        Ok(_) => panic!("Should not succeed"),
+        Err(model::Error::EntityNotFound(typ, id)) => {
+            assert_eq!("users", typ);
+            assert_eq!(999.to_string(), id);
+        }
+        other_error => panic!("Wrong Error {:?} ", other_error),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use super::{
+    end_session, hash_pwd, hash_pwd_blocking, new_session_token, now_sec, rotate_session,
+    utx_from_token, verify_pwd, verify_pwd_blocking, Error, TokenClaims,
+};
+use crate::config::Config;
+use crate::model::{init_db, SessionMac};
+
+/// Test password hash and verify
+#[test]
+fn security_hash_verify_pwd() -> Result<(), Box<dyn std::error::Error>> {
+    // -- ACTION
+    let pwd_hash = hash_pwd("welcome")?;
+
+    // -- CHECK
+    assert_ne!("welcome", pwd_hash, "hash should not be the clear pwd");
+    verify_pwd("welcome", &pwd_hash)?;
+    assert!(matches!(
+        verify_pwd("not-welcome", &pwd_hash),
+        Err(Error::FailLogin)
+    ));
+
+    Ok(())
+}
+
+/// Test password hash and verify on the blocking pool (no user hash always fails)
+#[tokio::test]
+async fn security_hash_verify_pwd_blocking() -> Result<(), Box<dyn std::error::Error>> {
+    // -- ACTION
+    let pwd_hash = hash_pwd_blocking("welcome".to_string()).await?;
+
+    // -- CHECK
+    verify_pwd_blocking("welcome".to_string(), Some(pwd_hash.clone())).await?;
+    assert!(matches!(
+        verify_pwd_blocking("not-welcome".to_string(), Some(pwd_hash)).await,
+        Err(Error::FailLogin)
+    ));
+    assert!(matches!(
+        verify_pwd_blocking("welcome".to_string(), None).await,
+        Err(Error::FailLogin)
+    ));
+
+    Ok(())
+}
+
+/// Test session token round trip
+#[tokio::test]
+async fn security_session_token() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+
+    // -- ACTION
+    let token = new_session_token(&db, 123).await?;
+    let utx = utx_from_token(&db, &token).await?;
+
+    // -- CHECK
+    assert_eq!(123, utx.user_id);
+
+    // -- CHECK - ended session
+    end_session(&db, &token).await?;
+    assert!(matches!(
+        utx_from_token(&db, &token).await,
+        Err(Error::TokenRevoked)
+    ));
+
+    Ok(())
+}
+
+/// Test session token rotation
+#[tokio::test]
+async fn security_rotate_session() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let token = new_session_token(&db, 123).await?;
+
+    // -- ACTION
+    let new_token = rotate_session(&db, &token).await?;
+
+    // -- CHECK
+    assert_ne!(token, new_token);
+    assert_eq!(123, utx_from_token(&db, &new_token).await?.user_id);
+    assert!(matches!(
+        utx_from_token(&db, &token).await,
+        Err(Error::TokenRevoked)
+    ));
+
+    Ok(())
+}
+
+/// Test concurrent session token rotations (only one succeeds)
+#[tokio::test]
+async fn security_rotate_session_concurrent() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let token = new_session_token(&db, 123).await?;
+
+    // -- ACTION
+    let (result_1, result_2) =
+        tokio::join!(rotate_session(&db, &token), rotate_session(&db, &token));
+
+    // -- CHECK
+    let (new_token, other) = match result_1 {
+        Ok(new_token) => (new_token, result_2),
+        Err(_) => (result_2?, result_1),
+    };
+    assert!(matches!(other, Err(Error::TokenRevoked)));
+    assert_eq!(123, utx_from_token(&db, &new_token).await?.user_id);
+
+    Ok(())
+}
+
+/// Test tampered tokens
+#[tokio::test]
+async fn security_utx_from_token_tampered() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let token = new_session_token(&db, 123).await?;
+    // same signature, other user id
+    let (_, rest) = token.split_once('.').unwrap();
+    let forged_token = format!("124.{}", rest);
+
+    // -- CHECK
+    for token in ["123", "", "1.2.3", forged_token.as_str()] {
+        assert!(
+            matches!(utx_from_token(&db, token).await, Err(Error::TokenTampered)),
+            "token '{}' should be tampered",
+            token
+        );
+    }
+
+    Ok(())
+}
+
+/// Test a signed token with other claims than its session (forged far-future exp)
+#[tokio::test]
+async fn security_utx_from_token_other_exp() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let session = SessionMac::create(&db, 123, now_sec() + 60).await?;
+    let token = TokenClaims {
+        user_id: 123,
+        session_id: session.id,
+        exp: now_sec() + 60 * 60 * 24 * 365,
+    }
+    .sign();
+
+    // -- ACTION
+    let result = utx_from_token(&db, &token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::TokenRevoked)));
+
+    Ok(())
+}
+
+/// Test expired token
+#[tokio::test]
+async fn security_utx_from_token_expired() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let exp = now_sec() - 10;
+    let session = SessionMac::create(&db, 123, exp).await?;
+    let token = TokenClaims {
+        user_id: 123,
+        session_id: session.id,
+        exp,
+    }
+    .sign();
+
+    // -- ACTION
+    let result = utx_from_token(&db, &token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::TokenExpired)));
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use super::budget_rest_filters;
+use crate::config::Config;
+use crate::model::{init_db, BudgetLevel, BudgetStatus, GroceryWithBudget};
+use crate::security::new_session_token;
+use crate::web::grocery::grocery_rest_filters;
+use crate::web::handle_rejection;
+use anyhow::{Context, Ok, Result};
+use serde::Deserialize;
+use serde_json::{from_str, from_value, json, Value};
+use std::str::from_utf8;
+use std::sync::Arc;
+use warp::hyper::body::Bytes;
+use warp::hyper::Response;
+use warp::Filter;
+
+/// Test shopping list budget set, and grocery create budget status
+#[tokio::test]
+async fn web_budget_list_set_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let apis = budget_rest_filters("api", db.clone())
+        .or(grocery_rest_filters("api", db.clone()))
+        .recover(handle_rejection);
+
+    // -- ACTION - set budget
+    let resp = warp::test::request()
+        .method("PUT")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/budget")
+        .json(&json!({ "limit": 100 }))
+        .reply(&apis)
+        .await;
+
+    // -- CHECK - set budget
+    assert_eq!(200, resp.status(), "http status");
+    let status: BudgetStatus = extract_body_data(resp)?;
+    assert_eq!(100, status.limit);
+    assert_eq!(75, status.total);
+    assert_eq!(BudgetLevel::Under, status.level);
+
+    // -- ACTION - create grocery going over
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries")
+        .json(&json!({ "list_id": 10, "name": "test - web_budget_list_set_ok", "cost": 30 }))
+        .reply(&apis)
+        .await;
+
+    // -- CHECK - grocery budget status
+    assert_eq!(200, resp.status(), "http status");
+    let grocery: GroceryWithBudget = extract_body_data(resp)?;
+    assert_eq!("test - web_budget_list_set_ok", grocery.grocery.name);
+    let status = grocery.budget.expect("should have a budget status");
+    assert_eq!(BudgetLevel::Over, status.level);
+    assert_eq!(5, status.overshoot);
+
+    Ok(())
+}
+
+/// Test strict user budget rejects grocery create
+#[tokio::test]
+async fn web_budget_user_strict() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let apis = budget_rest_filters("api", db.clone())
+        .or(grocery_rest_filters("api", db.clone()))
+        .recover(handle_rejection);
+
+    // -- ACTION - set budget
+    let resp = warp::test::request()
+        .method("PUT")
+        .header("X-Auth-Token", &token)
+        .path("/api/budget")
+        .json(&json!({ "limit": 10, "strict": true }))
+        .reply(&apis)
+        .await;
+    assert_eq!(200, resp.status(), "http status");
+
+    // -- ACTION - create grocery going over
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries")
+        .json(&json!({ "name": "test - web_budget_user_strict", "cost": 30 }))
+        .reply(&apis)
+        .await;
+
+    // -- CHECK
+    assert_ne!(200, resp.status(), "http status");
+
+    Ok(())
+}
+
+// region:    Web Test Utils
+/// Extract the data from the response
+fn extract_body_data<D>(resp: Response<Bytes>) -> Result<D>
+where
+    for<'de> D: Deserialize<'de>,
+{
+    // parse the body as serde_json::Value
+    let body = from_utf8(resp.body())?;
+    let mut body: Value = from_str(body)
+        .with_context(|| format!("Cannot parse resp.body to JSON. resp.body: '{}'", body))?;
+
+    // extract the data
+    let data = body["data"].take();
+
+    // deserialize the data to D
+    let data: D = from_value(data)?;
+
+    Ok(data)
+}
+// endregion: Web Test Utils
@@ -0,0 +1,146 @@
+use super::category_rest_filters;
+use crate::config::Config;
+use crate::model::{init_db, Category};
+use crate::security::new_session_token;
+use crate::web::handle_rejection;
+use anyhow::{Context, Ok, Result};
+use serde::Deserialize;
+use serde_json::{from_str, from_value, json, Value};
+use std::str::from_utf8;
+use std::sync::Arc;
+use warp::hyper::body::Bytes;
+use warp::hyper::Response;
+use warp::Filter;
+
+/// Test category list
+#[tokio::test]
+async fn web_category_list() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let category_apis = category_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/categories")
+        .reply(&category_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let categories: Vec<Category> = extract_body_data(resp)?;
+    assert_eq!(2, categories.len());
+    assert_eq!("produce", categories[0].name);
+    assert_eq!(Some("1".to_string()), categories[0].aisle);
+    assert_eq!("dairy", categories[1].name);
+
+    Ok(())
+}
+
+/// Test category create, update and delete
+#[tokio::test]
+async fn web_category_create_update_delete() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let category_apis = category_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION - create
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/categories")
+        .json(&json!({ "name": "bakery", "position": 5, "aisle": "B2" }))
+        .reply(&category_apis)
+        .await;
+
+    // -- CHECK - create
+    assert_eq!(200, resp.status(), "http status");
+    let category: Category = extract_body_data(resp)?;
+    assert!(category.id >= 1000, "category.id should be >= to 1000");
+    assert_eq!("bakery", category.name);
+
+    // -- ACTION - update
+    let resp = warp::test::request()
+        .method("PATCH")
+        .header("X-Auth-Token", &token)
+        .path(&format!("/api/categories/{}", category.id))
+        .json(&json!({ "position": 0 }))
+        .reply(&category_apis)
+        .await;
+
+    // -- CHECK - update
+    assert_eq!(200, resp.status(), "http status");
+    let category: Category = extract_body_data(resp)?;
+    assert_eq!(0, category.position);
+    assert_eq!(Some("B2".to_string()), category.aisle);
+
+    // -- ACTION - delete
+    let resp = warp::test::request()
+        .method("DELETE")
+        .header("X-Auth-Token", &token)
+        .path(&format!("/api/categories/{}", category.id))
+        .reply(&category_apis)
+        .await;
+
+    // -- CHECK - delete
+    assert_eq!(200, resp.status(), "http status");
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path(&format!("/api/categories/{}", category.id))
+        .reply(&category_apis)
+        .await;
+    assert_eq!(404, resp.status(), "http status");
+
+    Ok(())
+}
+
+/// Test category create with a name already taken
+#[tokio::test]
+async fn web_category_create_conflict() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let category_apis = category_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/categories")
+        .json(&json!({ "name": "dairy" }))
+        .reply(&category_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(409, resp.status(), "http status");
+
+    Ok(())
+}
+
+// region:    Web Test Utils
+/// Extract the data from the response
+fn extract_body_data<D>(resp: Response<Bytes>) -> Result<D>
+where
+    for<'de> D: Deserialize<'de>,
+{
+    // parse the body as serde_json::Value
+    let body = from_utf8(resp.body())?;
+    let mut body: Value = from_str(body)
+        .with_context(|| format!("Cannot parse resp.body to JSON. resp.body: '{}'", body))?;
+
+    // extract the data
+    let data = body["data"].take();
+
+    // deserialize the data to D
+    let data: D = from_value(data)?;
+
+    Ok(data)
+}
+// endregion: Web Test Utils
@@ -0,0 +1,147 @@
+use super::email_rest_filters;
+use crate::config::Config;
+use crate::mail::Mailer;
+use crate::model::{init_db, ListMemberMac, ListRole};
+use crate::security::{new_session_token, UserCtx};
+use crate::web::handle_rejection;
+use anyhow::Result;
+use serde_json::{from_str, json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::str::from_utf8;
+use std::sync::Arc;
+use warp::Filter;
+
+/// Test email of a shopping list to the file transport
+#[tokio::test]
+async fn web_email_list_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let dir = new_mail_dir("web_email_list_ok")?;
+    let mailer = Arc::new(Mailer::new_file(dir.clone(), "noreply@localhost")?);
+    let apis = email_rest_filters("api", db, mailer).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/email")
+        .json(&json!({ "to": "demo@localhost" }))
+        .reply(&apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let files = fs::read_dir(&dir)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(1, files.len(), "number of emails");
+    let eml = fs::read_to_string(files[0].path())?;
+    assert!(eml.contains("Subject: Shopping list - weekly"));
+    assert!(eml.contains("banana"));
+    assert!(eml.contains("orange"));
+    assert!(eml.contains("text/html"));
+
+    Ok(())
+}
+
+/// Test email of a shopping list by a non member
+#[tokio::test]
+async fn web_email_list_not_member() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 124).await?;
+    let dir = new_mail_dir("web_email_list_not_member")?;
+    let mailer = Arc::new(Mailer::new_file(dir.clone(), "noreply@localhost")?);
+    let apis = email_rest_filters("api", db, mailer).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/email")
+        .json(&json!({ "to": "demo2@localhost" }))
+        .reply(&apis)
+        .await;
+
+    // -- CHECK
+    assert_ne!(200, resp.status(), "http status");
+    assert_eq!(0, fs::read_dir(&dir)?.count(), "number of emails");
+
+    Ok(())
+}
+
+/// Test email of a shopping list by a viewer (403)
+#[tokio::test]
+async fn web_email_list_viewer() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let owner = UserCtx { user_id: 123 };
+    ListMemberMac::invite(&db, &owner, 10, 124, ListRole::Viewer).await?;
+    ListMemberMac::accept(&db, &UserCtx { user_id: 124 }, 10).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 124).await?;
+    let dir = new_mail_dir("web_email_list_viewer")?;
+    let mailer = Arc::new(Mailer::new_file(dir.clone(), "noreply@localhost")?);
+    let apis = email_rest_filters("api", db, mailer).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/email")
+        .json(&json!({ "to": "anyone@example.com" }))
+        .reply(&apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(403, resp.status(), "http status");
+    assert_eq!(0, fs::read_dir(&dir)?.count(), "number of emails");
+
+    Ok(())
+}
+
+/// Test email of a shopping list over the rate limit of the user (429, an invalid address does not count)
+#[tokio::test]
+async fn web_email_list_rate_limited() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let dir = new_mail_dir("web_email_list_rate_limited")?;
+    let mailer = Mailer::new_file(dir.clone(), "noreply@localhost")?.with_rate_limit(1);
+    let apis = email_rest_filters("api", db, Arc::new(mailer)).recover(handle_rejection);
+
+    // -- ACTION / CHECK
+    for (to, status) in [
+        ("not an email", 422),
+        ("demo@localhost", 200),
+        ("demo@localhost", 429),
+    ] {
+        let resp = warp::test::request()
+            .method("POST")
+            .header("X-Auth-Token", &token)
+            .path("/api/lists/10/email")
+            .json(&json!({ "to": to }))
+            .reply(&apis)
+            .await;
+        assert_eq!(status, resp.status(), "http status for '{}'", to);
+        if status == 429 {
+            let body: Value = from_str(from_utf8(resp.body())?)?;
+            assert_eq!("RATE_LIMITED", body["code"]);
+        }
+    }
+    assert_eq!(1, fs::read_dir(&dir)?.count(), "number of emails");
+
+    Ok(())
+}
+
+// region:    Web Test Utils
+fn new_mail_dir(name: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("shopping-list-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+// endregion: Web Test Utils
@@ -0,0 +1,32 @@
+use super::request_id;
+use warp::http::HeaderValue;
+
+/// Test the request id is taken from the caller when valid
+#[test]
+fn web_filter_log_request_id_from_header() {
+    // -- FIXTURE
+    let header = HeaderValue::from_static("3f2a-77c1");
+
+    // -- ACTION
+    let id = request_id(Some(&header));
+
+    // -- CHECK
+    assert_eq!("3f2a-77c1", id);
+}
+
+/// Test a new request id is generated when missing or invalid
+#[test]
+fn web_filter_log_request_id_generated() {
+    // -- FIXTURE
+    let header = HeaderValue::from_static("bad id");
+
+    // -- ACTION
+    let missing_id = request_id(None);
+    let invalid_id = request_id(Some(&header));
+
+    // -- CHECK
+    for id in [missing_id, invalid_id] {
+        assert_eq!(16, id.len());
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -1,6 +1,11 @@
 use super::grocery_rest_filters;
-use crate::model::{init_db, Grocery, GroceryMac, GroceryStatus};
-use crate::security::utx_from_token;
+use crate::config::Config;
+use crate::model::{
+    init_db, Budget, BudgetMac, Grocery, GroceryListOptions, GroceryMac, GroceryOpKind,
+    GroceryOpResult, GroceryStatus, GrocerySummary, ListMemberMac, ListRole,
+};
+use crate::security::UserCtx;
+use crate::security::{new_session_token, utx_from_token};
 use crate::web::handle_rejection;
 use anyhow::{Context, Ok, Result};
 use serde::Deserialize;
@@ -15,14 +20,15 @@ use warp::Filter;
 #[tokio::test]
 async fn web_grocery_list() -> Result<()> {
     // -- FIXTURE
-    let db = init_db().await?;
+    let db = init_db(&Config::load()?.db).await?;
     let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
     let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
 
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "123")
+        .header("X-Auth-Token", &token)
         .path("/api/groceries")
         .reply(&grocery_apis)
         .await;
@@ -42,18 +48,136 @@ async fn web_grocery_list() -> Result<()> {
     Ok(())
 }
 
+/// Test grocery list with a filter, and the pages by the next cursor
+#[tokio::test]
+async fn web_grocery_list_pages() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION - filter
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries?status=basket")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - filter
+    assert_eq!(200, resp.status(), "http status");
+    let body: Value = from_str(from_utf8(resp.body())?)?;
+    assert_eq!(Value::Null, body["next"]);
+    let groceries: Vec<Grocery> = from_value(body["data"].clone())?;
+    assert_eq!(1, groceries.len(), "number of groceries");
+    assert_eq!("banana", groceries[0].name);
+
+    // -- ACTION - pages
+    let mut names = Vec::new();
+    let mut path = "/api/groceries?sort=cost&limit=1".to_string();
+    loop {
+        let resp = warp::test::request()
+            .method("GET")
+            .header("X-Auth-Token", &token)
+            .path(&path)
+            .reply(&grocery_apis)
+            .await;
+        assert_eq!(200, resp.status(), "http status");
+        let body: Value = from_str(from_utf8(resp.body())?)?;
+        let groceries: Vec<Grocery> = from_value(body["data"].clone())?;
+        names.extend(groceries.into_iter().map(|g| g.name));
+        match body["next"].as_str() {
+            Some(next) => path = format!("/api/groceries?sort=cost&limit=1&cursor={}", next),
+            None => break,
+        }
+    }
+
+    // -- CHECK - pages
+    assert_eq!(vec!["banana", "orange"], names);
+
+    Ok(())
+}
+
+/// Test grocery list with an invalid query
+#[tokio::test]
+async fn web_grocery_list_invalid_query() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    for (path, status, code) in [
+        ("/api/groceries?sort=price", 400, "INVALID_QUERY"),
+        ("/api/groceries?limit=0", 422, "VALIDATION"),
+        ("/api/groceries?cursor=abc", 422, "VALIDATION"),
+    ] {
+        // -- ACTION
+        let resp = warp::test::request()
+            .method("GET")
+            .header("X-Auth-Token", &token)
+            .path(path)
+            .reply(&grocery_apis)
+            .await;
+
+        // -- CHECK
+        assert_eq!(status, resp.status(), "http status of {}", path);
+        assert_eq!(code, extract_body_error_code(resp)?, "code of {}", path);
+    }
+
+    Ok(())
+}
+
+/// Test grocery search
+#[tokio::test]
+async fn web_grocery_search() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/search?q=banan")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let groceries: Vec<Grocery> = extract_body_data(resp)?;
+    assert_eq!(1, groceries.len(), "number of groceries");
+    assert_eq!(100, groceries[0].id);
+
+    // -- ACTION / CHECK - missing q
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/search")
+        .reply(&grocery_apis)
+        .await;
+    assert_eq!(422, resp.status(), "http status");
+    assert_eq!("VALIDATION", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
 /// Test grocery get
 #[tokio::test]
 async fn web_grocery_get_ok() -> Result<()> {
     // -- FIXTURE
-    let db = init_db().await?;
+    let db = init_db(&Config::load()?.db).await?;
     let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
     let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
 
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "123")
+        .header("X-Auth-Token", &token)
         .path("/api/groceries/100")
         .reply(&grocery_apis)
         .await;
@@ -77,8 +201,9 @@ async fn web_grocery_get_ok() -> Result<()> {
 #[tokio::test]
 async fn web_grocery_create_ok() -> Result<()> {
     // -- FIXTURE
-    let db = init_db().await?;
+    let db = init_db(&Config::load()?.db).await?;
     let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
     let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
 
     // new grocery fixture
@@ -92,7 +217,7 @@ async fn web_grocery_create_ok() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("POST")
-        .header("X-Auth-Token", "123")
+        .header("X-Auth-Token", &token)
         .path("/api/groceries")
         .json(&body)
         .reply(&grocery_apis)
@@ -117,8 +242,9 @@ async fn web_grocery_create_ok() -> Result<()> {
 #[tokio::test]
 async fn web_grocery_update_ok() -> Result<()> {
     // -- FIXTURE
-    let db = init_db().await?;
+    let db = init_db(&Config::load()?.db).await?;
     let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
     let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
     // updated grocery
     const NAME: &str = "test - grocery 100 updated";
@@ -132,7 +258,7 @@ async fn web_grocery_update_ok() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("PATCH")
-        .header("X-Auth-Token", "123")
+        .header("X-Auth-Token", &token)
         .path("/api/groceries/100")
         .json(&body)
         .reply(&grocery_apis)
@@ -157,14 +283,15 @@ async fn web_grocery_update_ok() -> Result<()> {
 #[tokio::test]
 async fn web_grocery_delete_ok() -> Result<()> {
     // -- FIXTURE
-    let db = init_db().await?;
+    let db = init_db(&Config::load()?.db).await?;
     let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
     let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
 
     // -- ACTION
     let resp = warp::test::request()
         .method("DELETE")
-        .header("X-Auth-Token", "123")
+        .header("X-Auth-Token", &token)
         .path("/api/groceries/100")
         .reply(&grocery_apis)
         .await;
@@ -182,15 +309,395 @@ async fn web_grocery_delete_ok() -> Result<()> {
     assert_eq!(GroceryStatus::Basket, grocery.status);
 
     // -- CHECK - list .len() should be 1
-    let utx = utx_from_token(&db, "123").await?;
-    let groceries = GroceryMac::list(&db, &utx).await?;
+    let utx = utx_from_token(&db, &token).await?;
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
     assert_eq!(1, groceries.len(), "groceries length");
     assert_eq!(101, groceries[0].id, "Grocery remaining should be 101");
 
     Ok(())
 }
 
+/// Test grocery reorder
+#[tokio::test]
+async fn web_grocery_reorder_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("PATCH")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/reorder")
+        .json(&json!({ "ids": [100, 101] }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - status
+    assert_eq!(200, resp.status(), "http status");
+
+    // -- CHECK - list order
+    let utx = utx_from_token(&db, &token).await?;
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    assert_eq!(100, groceries[0].id);
+    assert_eq!(101, groceries[1].id);
+
+    Ok(())
+}
+
+/// Test grocery batch (the result of each operation)
+#[tokio::test]
+async fn web_grocery_batch_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/batch")
+        .json(&json!({ "ops": [
+            { "op": "create", "data": { "name": "rice", "cost": 10 } },
+            { "op": "update", "id": 101, "data": { "status": "basket" } },
+            { "op": "delete", "id": 100 },
+        ] }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - status
+    assert_eq!(200, resp.status(), "http status");
+
+    // -- CHECK - .data (results)
+    let results: Vec<GroceryOpResult> = extract_body_data(resp)?;
+    assert_eq!(3, results.len());
+    assert_eq!(GroceryOpKind::Create, results[0].op);
+    assert_eq!("rice", results[0].grocery.name);
+    assert_eq!(GroceryOpKind::Update, results[1].op);
+    assert_eq!(GroceryStatus::Basket, results[1].grocery.status);
+    assert_eq!(GroceryOpKind::Delete, results[2].op);
+    assert_eq!(100, results[2].grocery.id);
+
+    Ok(())
+}
+
+/// Test grocery batch failure (nothing applied, validation errors of the failed operation)
+#[tokio::test]
+async fn web_grocery_batch_error() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/batch")
+        .json(&json!({ "ops": [
+            { "op": "delete", "id": 100 },
+            { "op": "create", "data": { "name": " " } },
+        ] }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - status and failed operation
+    assert_eq!(422, resp.status(), "http status");
+    let body: Value = from_str(from_utf8(resp.body())?)?;
+    assert_eq!("VALIDATION", body["code"]);
+    assert!(body["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("operation 1"));
+    assert_eq!(1, body["index"], "failed operation index");
+    assert_eq!(
+        json!([{ "field": "name", "message": "cannot be empty" }]),
+        body["fields"]
+    );
+
+    // -- CHECK - nothing applied
+    let utx = utx_from_token(&db, &token).await?;
+    let groceries = GroceryMac::list(&db, &utx, &GroceryListOptions::default()).await?;
+    assert_eq!(2, groceries.len(), "groceries length");
+
+    Ok(())
+}
+
+/// Test grocery summary
+#[tokio::test]
+async fn web_grocery_summary_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/summary?list_id=10")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - status
+    assert_eq!(200, resp.status(), "http status");
+
+    // -- CHECK - .data (summary)
+    let summary: GrocerySummary = extract_body_data(resp)?;
+    assert_eq!(75, summary.total_cost);
+    assert_eq!(25, summary.basket_cost);
+    assert_eq!(50, summary.shelf_cost);
+    assert_eq!(2, summary.total_count);
+
+    Ok(())
+}
+
+/// Test error status and code - entity not found (404)
+#[tokio::test]
+async fn web_grocery_error_not_found() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/9999")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(404, resp.status(), "http status");
+    assert_eq!("ENTITY_NOT_FOUND", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
+/// Test error status and code - missing and invalid auth (401)
+#[tokio::test]
+async fn web_grocery_error_unauthorized() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION - missing token
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/api/groceries")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - missing token
+    assert_eq!(401, resp.status(), "http status");
+    assert_eq!("AUTH_MISSING", extract_body_error_code(resp)?);
+
+    // -- ACTION - invalid token
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", "123")
+        .path("/api/groceries")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK - invalid token
+    assert_eq!(401, resp.status(), "http status");
+    assert_eq!("TOKEN_INVALID", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
+/// Test error status and code - viewer editing a shopping list grocery (403)
+#[tokio::test]
+async fn web_grocery_error_forbidden() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    ListMemberMac::invite(&db, &UserCtx { user_id: 123 }, 10, 124, ListRole::Viewer).await?;
+    ListMemberMac::accept(&db, &UserCtx { user_id: 124 }, 10).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 124).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("PATCH")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries/100")
+        .json(&json!({ "name": "test - web_grocery_error_forbidden" }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(403, resp.status(), "http status");
+    assert_eq!("USER_NOT_AUTHORIZED", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
+/// Test error status and code - strict budget exceeded (422)
+#[tokio::test]
+async fn web_grocery_error_unprocessable() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let budget = Budget {
+        limit: Some(80),
+        strict: true,
+    };
+    BudgetMac::set_for_list(&db, &UserCtx { user_id: 123 }, 10, budget).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries")
+        .json(
+            &json!({ "list_id": 10, "name": "test - web_grocery_error_unprocessable", "cost": 10 }),
+        )
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(422, resp.status(), "http status");
+    assert_eq!("BUDGET_EXCEEDED", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
+/// Test error status and code - validation with field errors (422)
+#[tokio::test]
+async fn web_grocery_error_validation() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries")
+        .json(&json!({ "name": " ", "cost": -5 }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(422, resp.status(), "http status");
+    let body: Value = from_str(from_utf8(resp.body())?)?;
+    assert_eq!("VALIDATION", body["code"]);
+    assert_eq!(
+        json!([
+            { "field": "name", "message": "cannot be empty" },
+            { "field": "cost", "message": "cannot be negative" },
+        ]),
+        body["fields"]
+    );
+
+    Ok(())
+}
+
+/// Test error status and code - database error (500, without details)
+#[tokio::test]
+async fn web_grocery_error_database() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION (postgres text cannot contain a NUL character)
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries")
+        .json(&json!({ "name": "test - \u{0}" }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(500, resp.status(), "http status");
+    let body: Value = from_str(from_utf8(resp.body())?)?;
+    assert_eq!("DATABASE_ERROR", body["code"]);
+    assert_eq!("Internal server error", body["message"]);
+
+    Ok(())
+}
+
+/// Test error status and code - method not allowed (405)
+#[tokio::test]
+async fn web_grocery_error_method_not_allowed() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("PUT")
+        .header("X-Auth-Token", &token)
+        .path("/api/groceries")
+        .json(&json!({ "name": "test - web_grocery_error_method_not_allowed" }))
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(405, resp.status(), "http status");
+    assert_eq!("METHOD_NOT_ALLOWED", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
+/// Test error status and code - unknown route (404)
+#[tokio::test]
+async fn web_grocery_error_unknown_route() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let grocery_apis = grocery_rest_filters("api", db).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/unknown")
+        .reply(&grocery_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(404, resp.status(), "http status");
+    assert_eq!("NOT_FOUND", extract_body_error_code(resp)?);
+
+    Ok(())
+}
+
 // region:    Web Test Utils
+/// Extract the error code from the response
+fn extract_body_error_code(resp: Response<Bytes>) -> Result<String> {
+    let body = from_utf8(resp.body())?;
+    let body: Value = from_str(body)
+        .with_context(|| format!("Cannot parse resp.body to JSON. resp.body: '{}'", body))?;
+
+    let code = body["code"]
+        .as_str()
+        .with_context(|| format!("No error code in resp.body: '{}'", body))?;
+
+    Ok(code.to_string())
+}
+
 /// Extract the data from the response
 fn extract_body_data<D>(resp: Response<Bytes>) -> Result<D>
 where
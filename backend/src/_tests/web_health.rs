@@ -0,0 +1,105 @@
+use super::health_filters;
+use crate::config::Config;
+use crate::model::{expected_migrations, init_db};
+use anyhow::Result;
+use serde_json::{from_slice, Value};
+use std::sync::Arc;
+
+/// Test the liveness probe
+#[tokio::test]
+async fn web_health_healthz() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    let health_apis = health_filters(db, Arc::new(expected_migrations()?));
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/healthz")
+        .reply(&health_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let body: Value = from_slice(resp.body())?;
+    assert_eq!("ok", body["status"]);
+
+    Ok(())
+}
+
+/// Test the readiness probe on a migrated db
+#[tokio::test]
+async fn web_health_readyz() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    let health_apis = health_filters(db, Arc::new(expected_migrations()?));
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/readyz")
+        .reply(&health_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let body: Value = from_slice(resp.body())?;
+    assert_eq!("ready", body["status"]);
+    assert!(body["migration"].as_i64().is_some());
+
+    Ok(())
+}
+
+/// Test the readiness probe with a pending migration
+#[tokio::test]
+async fn web_health_readyz_pending_migration() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    sqlx::query("DELETE FROM schema_migrations WHERE version = 1")
+        .execute(&*db)
+        .await?;
+    let health_apis = health_filters(db, Arc::new(expected_migrations()?));
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/readyz")
+        .reply(&health_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(503, resp.status(), "http status");
+    let body: Value = from_slice(resp.body())?;
+    assert_eq!("not ready", body["status"]);
+    assert!(body["reason"].as_str().unwrap().contains("Pending"));
+
+    Ok(())
+}
+
+/// Test the readiness probe is read only (no tracking table created)
+#[tokio::test]
+async fn web_health_readyz_read_only() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    sqlx::query("DROP TABLE schema_migrations")
+        .execute(&*db)
+        .await?;
+    let health_apis = health_filters(db.clone(), Arc::new(expected_migrations()?));
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/readyz")
+        .reply(&health_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(503, resp.status(), "http status");
+    let (table,): (Option<String>,) =
+        sqlx::query_as("SELECT to_regclass('schema_migrations')::text")
+            .fetch_one(&*db)
+            .await?;
+    assert_eq!(None, table, "the tracking table should not be created");
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use super::{metrics_filters, observe_rejection, observe_request, route, route_label, route_scope};
+use crate::config::Config;
+use crate::model::init_db;
+use crate::security::new_session_token;
+use crate::web::{grocery_rest_filters, health_filters};
+use anyhow::Result;
+use std::str::from_utf8;
+use std::sync::Arc;
+use std::time::Duration;
+use warp::Filter;
+
+/// Test the route labels (set by the matched route filter)
+#[tokio::test]
+async fn web_metrics_route_label() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    let token = new_session_token(&db, 123).await?;
+    let apis = grocery_rest_filters("api", db.clone())
+        .or(health_filters(db, Arc::new(Vec::new())))
+        .or(warp::path("static").and(route("static")).map(warp::reply));
+
+    // -- ACTION / CHECK (the auth failures keep the label of the matched route)
+    let auth = Some(token.as_str());
+    for (path, token, label) in [
+        ("/api/groceries", auth, "/api/groceries"),
+        ("/api/groceries/100", auth, "/api/groceries/:id"),
+        ("/api/groceries/summary", auth, "/api/groceries/summary"),
+        ("/api/groceries", None, "/api/groceries"),
+        ("/api/groceries/100", None, "/api/groceries/:id"),
+        (
+            "/api/groceries/100",
+            Some("bad-token"),
+            "/api/groceries/:id",
+        ),
+        ("/healthz", None, "/healthz"),
+        ("/static", None, "static"),
+        ("/api/groceries/banana", auth, "other"),
+        ("/api/unknown", auth, "other"),
+    ] {
+        let mut request = warp::test::request().method("GET").path(path);
+        if let Some(token) = token {
+            request = request.header("X-Auth-Token", token);
+        }
+        let route = route_scope(async {
+            request.reply(&apis).await;
+            route_label()
+        })
+        .await;
+        assert_eq!(label, &*route, "route label of '{}'", path);
+    }
+
+    // -- CHECK - no route label outside of a route scope
+    assert_eq!("other", &*route_label());
+
+    Ok(())
+}
+
+/// Test the metrics endpoint
+#[tokio::test]
+async fn web_metrics_get() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    let metrics_apis = metrics_filters(db);
+    route_scope(async {
+        let route = route("/api/groceries/:id");
+        warp::test::request().filter(&route).await.ok();
+        observe_request("GET", 200, Duration::from_millis(12));
+    })
+    .await;
+    observe_rejection("model::Error");
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/metrics")
+        .reply(&metrics_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let body = from_utf8(resp.body())?;
+    assert!(body
+        .contains(r#"http_requests_total{method="GET",route="/api/groceries/:id",status="200"}"#));
+    assert!(body.contains(
+        r#"http_request_duration_seconds_count{method="GET",route="/api/groceries/:id"}"#
+    ));
+    assert!(body.contains(r#"http_rejections_total{typ="model::Error"}"#));
+    assert!(body.contains(r#"db_pool_connections{state="in_use"}"#));
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use super::{start_web, Error};
+use crate::config::{Config, WebConfig};
+use crate::mail::Mailer;
+use crate::model::{expected_migrations, init_db};
+use anyhow::Result;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// Test the server serves, then ends on the shutdown signal
+#[tokio::test]
+async fn web_server_graceful_shutdown() -> Result<()> {
+    // -- FIXTURE
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    let mailer = Arc::new(Mailer::new_file(std::env::temp_dir(), "noreply@localhost")?);
+    let migrations = Arc::new(expected_migrations()?);
+    let config = new_web_config("web_server_graceful_shutdown", 18091)?;
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        start_web(&config, db, mailer, migrations, async {
+            shutdown_rx.await.ok();
+        })
+        .await
+    });
+
+    // -- ACTION - request
+    let response = get_index(18091).await?;
+
+    // -- CHECK - request
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+    // -- ACTION - shutdown
+    shutdown_tx.send(()).ok();
+    let result = tokio::time::timeout(Duration::from_secs(5), server).await??;
+
+    // -- CHECK - server ended
+    assert!(result.is_ok());
+    assert!(TcpStream::connect(("127.0.0.1", 18091)).await.is_err());
+
+    Ok(())
+}
+
+/// Test the server fails to start on a port already bound
+#[tokio::test]
+async fn web_server_bind_error() -> Result<()> {
+    // -- FIXTURE
+    let _listener = TcpListener::bind(("127.0.0.1", 18092)).await?;
+    let db = Arc::new(init_db(&Config::load()?.db).await?);
+    let mailer = Arc::new(Mailer::new_file(std::env::temp_dir(), "noreply@localhost")?);
+    let migrations = Arc::new(expected_migrations()?);
+    let config = new_web_config("web_server_bind_error", 18092)?;
+
+    // -- ACTION
+    let result = start_web(&config, db, mailer, migrations, std::future::pending()).await;
+
+    // -- CHECK
+    match result {
+        Err(Error::FailStartBind(addr, _)) => assert_eq!("127.0.0.1:18092", addr),
+        other => panic!("Wrong Result {:?} ", other),
+    }
+
+    Ok(())
+}
+
+// region:    Test Utils
+fn new_web_config(name: &str, port: u16) -> Result<WebConfig> {
+    let folder = std::env::temp_dir().join(format!("shopping-list-{}", name));
+    fs::create_dir_all(&folder)?;
+    fs::write(folder.join("index.html"), "<html></html>")?;
+
+    Ok(WebConfig {
+        host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        port,
+        folder: folder.to_str().unwrap().to_string(),
+    })
+}
+
+/// GET / with a raw http/1.1 request (retry until the server is up)
+async fn get_index(port: u16) -> Result<String> {
+    let mut stream = None;
+    for _ in 0..50 {
+        match TcpStream::connect(("127.0.0.1", port)).await {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    }
+    let mut stream = stream.ok_or_else(|| anyhow::anyhow!("server not started"))?;
+
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    Ok(response)
+}
+// endregion: Test Utils
@@ -0,0 +1,313 @@
+use super::shopping_list_rest_filters;
+use crate::config::Config;
+use crate::model::{init_db, Grocery, ListMember, ListMemberMac, ListRole, ShoppingList};
+use crate::security::{new_session_token, UserCtx};
+use crate::web::handle_rejection;
+use anyhow::{Context, Ok, Result};
+use serde::Deserialize;
+use serde_json::{from_str, from_value, json, Value};
+use std::str::from_utf8;
+use std::sync::Arc;
+use warp::hyper::body::Bytes;
+use warp::hyper::Response;
+use warp::Filter;
+
+/// Test shopping list list
+#[tokio::test]
+async fn web_shopping_list_list() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists")
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let shopping_lists: Vec<ShoppingList> = extract_body_data(resp)?;
+    assert_eq!(1, shopping_lists.len(), "number of shopping lists");
+    assert_eq!(10, shopping_lists[0].id);
+    assert_eq!("weekly", shopping_lists[0].name);
+
+    Ok(())
+}
+
+/// Test shopping list create
+#[tokio::test]
+async fn web_shopping_list_create_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+    const NAME: &str = "test - web_shopping_list_create_ok";
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists")
+        .json(&json!({ "name": NAME }))
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let shopping_list: ShoppingList = extract_body_data(resp)?;
+    assert!(
+        shopping_list.id >= 1000,
+        "shopping_list.id should be >= to 1000"
+    );
+    assert_eq!(NAME, shopping_list.name);
+
+    Ok(())
+}
+
+/// Test shopping list create and update with invalid patches (422)
+#[tokio::test]
+async fn web_shopping_list_create_update_invalid() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION / CHECK - create without name, with a blank name
+    for (body, field, message) in [
+        (json!({}), "name", "is required"),
+        (json!({ "name": "  " }), "name", "cannot be empty"),
+    ] {
+        let resp = warp::test::request()
+            .method("POST")
+            .header("X-Auth-Token", &token)
+            .path("/api/lists")
+            .json(&body)
+            .reply(&list_apis)
+            .await;
+        assert_eq!(422, resp.status(), "http status for {}", body);
+        let body: Value = from_str(from_utf8(resp.body())?)?;
+        assert_eq!("VALIDATION", body["code"]);
+        assert_eq!(
+            json!([{ "field": field, "message": message }]),
+            body["fields"]
+        );
+    }
+
+    // -- ACTION / CHECK - update with an empty patch, with a blank name
+    for (body, field, message) in [
+        (json!({}), "patch", "has no field to update"),
+        (json!({ "name": "" }), "name", "cannot be empty"),
+    ] {
+        let resp = warp::test::request()
+            .method("PATCH")
+            .header("X-Auth-Token", &token)
+            .path("/api/lists/10")
+            .json(&body)
+            .reply(&list_apis)
+            .await;
+        assert_eq!(422, resp.status(), "http status for {}", body);
+        let body: Value = from_str(from_utf8(resp.body())?)?;
+        assert_eq!("VALIDATION", body["code"]);
+        assert_eq!(
+            json!([{ "field": field, "message": message }]),
+            body["fields"]
+        );
+    }
+
+    Ok(())
+}
+
+/// Test shopping list groceries list
+#[tokio::test]
+async fn web_shopping_list_grocery_list() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/groceries")
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let groceries: Vec<Grocery> = extract_body_data(resp)?;
+    assert_eq!(2, groceries.len(), "number of groceries");
+    assert!(groceries.iter().all(|g| g.list_id == Some(10)));
+
+    Ok(())
+}
+
+/// Test shopping list grocery create
+#[tokio::test]
+async fn web_shopping_list_grocery_create_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 123).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+    const NAME: &str = "test - web_shopping_list_grocery_create_ok";
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/groceries")
+        .json(&json!({ "name": NAME, "cost": 10 }))
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let grocery: Grocery = extract_body_data(resp)?;
+    assert_eq!(NAME, grocery.name);
+    assert_eq!(Some(10), grocery.list_id);
+
+    Ok(())
+}
+
+/// Test shopping list groceries of another user
+#[tokio::test]
+async fn web_shopping_list_grocery_list_other_user() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let token = new_session_token(&db, 124).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &token)
+        .path("/api/lists/10/groceries")
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK
+    assert_ne!(200, resp.status(), "http status");
+
+    Ok(())
+}
+
+/// Test shopping list member invite and accept
+#[tokio::test]
+async fn web_shopping_list_member_invite_accept() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let owner_token = new_session_token(&db, 123).await?;
+    let member_token = new_session_token(&db, 124).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION - invite
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &owner_token)
+        .path("/api/lists/10/members")
+        .json(&json!({ "username": "demo2", "role": "Viewer" }))
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK - invite
+    assert_eq!(200, resp.status(), "http status");
+    let member: ListMember = extract_body_data(resp)?;
+    assert_eq!(124, member.user_id);
+    assert_eq!(ListRole::Viewer, member.role);
+    assert!(member.pending);
+
+    // -- ACTION - accept
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &member_token)
+        .path("/api/lists/10/accept")
+        .reply(&list_apis)
+        .await;
+
+    // -- CHECK - accept
+    assert_eq!(200, resp.status(), "http status");
+    let member: ListMember = extract_body_data(resp)?;
+    assert!(!member.pending);
+
+    // -- CHECK - member sees the list groceries
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", &member_token)
+        .path("/api/lists/10/groceries")
+        .reply(&list_apis)
+        .await;
+    assert_eq!(200, resp.status(), "http status");
+    let groceries: Vec<Grocery> = extract_body_data(resp)?;
+    assert_eq!(2, groceries.len(), "number of groceries");
+
+    Ok(())
+}
+
+/// Test shopping list member invite as owner (422), and by an editor (403, before the username lookup)
+#[tokio::test]
+async fn web_shopping_list_member_invite_refused() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    ListMemberMac::invite(&db, &UserCtx { user_id: 123 }, 10, 124, ListRole::Editor).await?;
+    ListMemberMac::accept(&db, &UserCtx { user_id: 124 }, 10).await?;
+    let db = Arc::new(db);
+    let owner_token = new_session_token(&db, 123).await?;
+    let editor_token = new_session_token(&db, 124).await?;
+    let list_apis = shopping_list_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION / CHECK
+    for (token, username, role, status) in [
+        (&owner_token, "demo2", "Owner", 422),
+        (&editor_token, "demo2", "Viewer", 403),
+        (&editor_token, "nobody", "Viewer", 403),
+    ] {
+        let resp = warp::test::request()
+            .method("POST")
+            .header("X-Auth-Token", token)
+            .path("/api/lists/10/members")
+            .json(&json!({ "username": username, "role": role }))
+            .reply(&list_apis)
+            .await;
+        assert_eq!(
+            status,
+            resp.status(),
+            "http status for {} {}",
+            username,
+            role
+        );
+    }
+
+    Ok(())
+}
+
+// region:    Web Test Utils
+/// Extract the data from the response
+fn extract_body_data<D>(resp: Response<Bytes>) -> Result<D>
+where
+    for<'de> D: Deserialize<'de>,
+{
+    // parse the body as serde_json::Value
+    let body = from_utf8(resp.body())?;
+    let mut body: Value = from_str(body)
+        .with_context(|| format!("Cannot parse resp.body to JSON. resp.body: '{}'", body))?;
+
+    // extract the data
+    let data = body["data"].take();
+
+    // deserialize the data to D
+    let data: D = from_value(data)?;
+
+    Ok(data)
+}
+// endregion: Web Test Utils
@@ -0,0 +1,218 @@
+use super::user_rest_filters;
+use crate::config::Config;
+use crate::model::{init_db, User};
+use crate::security::{new_session_token, utx_from_token};
+use crate::web::handle_rejection;
+use anyhow::{Context, Ok, Result};
+use serde::Deserialize;
+use serde_json::{from_str, from_value, json, Value};
+use std::str::from_utf8;
+use std::sync::Arc;
+use warp::hyper::body::Bytes;
+use warp::hyper::Response;
+use warp::Filter;
+
+/// Auth response data
+#[derive(Deserialize)]
+struct AuthData {
+    token: String,
+    user: User,
+}
+
+/// Test user login
+#[tokio::test]
+async fn web_user_login_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let user_apis = user_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/api/login")
+        .json(&json!({ "username": "demo", "pwd": "welcome" }))
+        .reply(&user_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let data: AuthData = extract_body_data(resp)?;
+    assert_eq!(123, data.user.id);
+    assert_eq!("demo", data.user.username);
+
+    // -- CHECK - token is a valid session
+    let utx = utx_from_token(&db, &data.token).await?;
+    assert_eq!(123, utx.user_id);
+
+    Ok(())
+}
+
+/// Test user login with wrong password
+#[tokio::test]
+async fn web_user_login_wrong_pwd() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let user_apis = user_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/api/login")
+        .json(&json!({ "username": "demo", "pwd": "not-welcome" }))
+        .reply(&user_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(401, resp.status(), "http status");
+
+    Ok(())
+}
+
+/// Test user login with an unknown username, an untrimmed username and a too long pwd
+#[tokio::test]
+async fn web_user_login_username_pwd() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let user_apis = user_rest_filters("api", db.clone()).recover(handle_rejection);
+    let long_pwd = "x".repeat(1025);
+
+    // -- ACTION / CHECK
+    for (username, pwd, status) in [
+        ("nobody", "welcome", 401),
+        (" demo ", "welcome", 200),
+        ("demo", long_pwd.as_str(), 401),
+    ] {
+        let resp = warp::test::request()
+            .method("POST")
+            .path("/api/login")
+            .json(&json!({ "username": username, "pwd": pwd }))
+            .reply(&user_apis)
+            .await;
+        assert_eq!(status, resp.status(), "http status for '{}'", username);
+    }
+
+    Ok(())
+}
+
+/// Test user register with a too long pwd
+#[tokio::test]
+async fn web_user_register_pwd_too_long() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let user_apis = user_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/api/register")
+        .json(&json!({ "username": "test - web_user_register_pwd_too_long", "pwd": "x".repeat(1025) }))
+        .reply(&user_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(422, resp.status(), "http status");
+    let body: Value = from_str(from_utf8(resp.body())?)?;
+    assert_eq!("VALIDATION", body["code"]);
+    assert_eq!("pwd", body["fields"][0]["field"]);
+
+    Ok(())
+}
+
+/// Test user register then logout
+#[tokio::test]
+async fn web_user_register_logout_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let user_apis = user_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION - register
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/api/register")
+        .json(&json!({ "username": "test - web_user_register", "pwd": "secret" }))
+        .reply(&user_apis)
+        .await;
+
+    // -- CHECK - register
+    assert_eq!(200, resp.status(), "http status");
+    let data: AuthData = extract_body_data(resp)?;
+    assert!(data.user.id >= 1000, "user.id should be >= to 1000");
+    let utx = utx_from_token(&db, &data.token).await?;
+    assert_eq!(data.user.id, utx.user_id);
+
+    // -- ACTION - logout
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &data.token)
+        .path("/api/logout")
+        .reply(&user_apis)
+        .await;
+
+    // -- CHECK - logout
+    assert_eq!(200, resp.status(), "http status");
+    assert!(utx_from_token(&db, &data.token).await.is_err());
+
+    // -- CHECK - revoked token is unauthorized
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &data.token)
+        .path("/api/logout")
+        .reply(&user_apis)
+        .await;
+    assert_eq!(401, resp.status(), "http status");
+
+    Ok(())
+}
+
+/// Test user refresh
+#[tokio::test]
+async fn web_user_refresh_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db(&Config::load()?.db).await?;
+    let db = Arc::new(db);
+    let user_apis = user_rest_filters("api", db.clone()).recover(handle_rejection);
+    let token = new_session_token(&db, 123).await?;
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", &token)
+        .path("/api/refresh")
+        .reply(&user_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let data: AuthData = extract_body_data(resp)?;
+    assert_ne!(token, data.token);
+    assert_eq!(123, utx_from_token(&db, &data.token).await?.user_id);
+    assert!(utx_from_token(&db, &token).await.is_err());
+
+    Ok(())
+}
+
+// region:    Web Test Utils
+/// Extract the data from the response
+fn extract_body_data<D>(resp: Response<Bytes>) -> Result<D>
+where
+    for<'de> D: Deserialize<'de>,
+{
+    // parse the body as serde_json::Value
+    let body = from_utf8(resp.body())?;
+    let mut body: Value = from_str(body)
+        .with_context(|| format!("Cannot parse resp.body to JSON. resp.body: '{}'", body))?;
+
+    // extract the data
+    let data = body["data"].take();
+
+    // deserialize the data to D
+    let data: D = from_value(data)?;
+
+    Ok(data)
+}
+// endregion: Web Test Utils
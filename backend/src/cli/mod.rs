@@ -0,0 +1,344 @@
+use crate::{
+    config::Config,
+    mail::{self, Mailer},
+    model::{
+        self, connect_db, expected_migrations, init_db, migrate_down, migrate_status, migrate_up,
+        seed_db, Db, Grocery, GroceryListOptions, GroceryMac, MigrationStatus, SessionMac, UserMac,
+        UserPatch,
+    },
+    security::{self, hash_pwd, UserCtx},
+    web::{self, shutdown_signal, start_web},
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+/// Cli - shopping list server and admin commands
+#[derive(Debug, Parser)]
+#[command(
+    name = "shopping-list",
+    about = "Shopping list server and admin commands"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the web server (default command)
+    Serve {
+        /// Web folder (overrides web.folder of the config)
+        #[arg(long)]
+        web_folder: Option<String>,
+    },
+    /// Manage the schema migrations
+    Migrate {
+        #[command(subcommand)]
+        command: MigrateCommand,
+    },
+    /// Insert the dev seed data
+    Seed,
+    /// Manage the users
+    User {
+        #[command(subcommand)]
+        command: UserCommand,
+    },
+    /// Export the groceries of a user
+    Export {
+        /// Username of the groceries owner or member
+        #[arg(long)]
+        user: String,
+        /// Only the groceries of this shopping list
+        #[arg(long)]
+        list: Option<i64>,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Output file (stdout if not set)
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Apply the pending migrations
+    Up,
+    /// Revert the last applied migration
+    Down,
+    /// Show the migrations status
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum UserCommand {
+    /// Create a user
+    Create {
+        username: String,
+        /// Password (read from stdin if not set)
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Set a new password, and revoke the user sessions
+    ResetPassword {
+        username: String,
+        /// Password (read from stdin if not set)
+        #[arg(long)]
+        password: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// run - execute a cli command (serve if none)
+pub async fn run(cli: Cli, config: Config) -> Result<(), Error> {
+    let command = cli.command.unwrap_or(Command::Serve { web_folder: None });
+
+    match command {
+        Command::Serve { web_folder } => serve(config, web_folder).await,
+        Command::Migrate { command } => migrate(config, command).await,
+        Command::Seed => {
+            // Note: the seed needs the schema, but never recreates the db (see db.recreate)
+            let db = connect_db(&config.db).await?;
+            migrate_up(&db).await?;
+            seed_db(&db).await?;
+            println!("Seed data inserted");
+            Ok(())
+        }
+        Command::User { command } => {
+            let db = connect_db(&config.db).await?;
+            user(&db, command).await
+        }
+        Command::Export {
+            user,
+            list,
+            format,
+            output,
+        } => {
+            let db = connect_db(&config.db).await?;
+            export(&db, &user, list, format, output).await
+        }
+    }
+}
+
+/// serve - start the web server
+async fn serve(mut config: Config, web_folder: Option<String>) -> Result<(), Error> {
+    if let Some(web_folder) = web_folder {
+        config.web.folder = web_folder;
+    }
+
+    // sign the session tokens with the configured key (the dev key only on a recreated dev db)
+    if config.security.token_key.is_none() && !config.db.recreate {
+        return Err(Error::MissingTokenKey);
+    }
+    security::init_token_key(&config.security);
+
+    // get the database
+    let db = init_db(&config.db).await?;
+    let db = Arc::new(db);
+    // the migration files, read once for the readiness checks
+    let migrations = Arc::new(expected_migrations()?);
+
+    // get the mailer
+    let mailer = Mailer::new(&config.mail)?;
+    let mailer = Arc::new(mailer);
+
+    // start the server (until SIGINT / SIGTERM, and the in-flight requests are drained)
+    start_web(
+        &config.web,
+        db.clone(),
+        mailer,
+        migrations,
+        shutdown_signal(),
+    )
+    .await?;
+
+    // close the db pool
+    db.close().await;
+    tracing::info!("server ended");
+
+    Ok(())
+}
+
+/// migrate - apply, revert or show the migrations (without applying them on connect)
+async fn migrate(config: Config, command: MigrateCommand) -> Result<(), Error> {
+    let db = connect_db(&config.db).await?;
+
+    match command {
+        MigrateCommand::Up => {
+            migrate_up(&db).await?;
+            print_migrations(&migrate_status(&db).await?);
+        }
+        MigrateCommand::Down => match migrate_down(&db).await? {
+            Some(_) => print_migrations(&migrate_status(&db).await?),
+            None => println!("No migration to revert"),
+        },
+        MigrateCommand::Status => print_migrations(&migrate_status(&db).await?),
+    }
+
+    Ok(())
+}
+
+/// user - create a user or reset its password
+async fn user(db: &Db, command: UserCommand) -> Result<(), Error> {
+    match command {
+        UserCommand::Create { username, password } => {
+            // same rule as the web register
+            let username = username.trim();
+            if username.is_empty() {
+                return Err(web::Error::RegisterEmptyCredentials.into());
+            }
+            let pwd = read_password(password)?;
+            let data = UserPatch {
+                username: Some(username.to_string()),
+                pwd: Some(hash_pwd(&pwd)?),
+            };
+            let user = UserMac::create(db, data).await?;
+            println!("User '{}' created (id {})", user.username, user.id);
+        }
+        UserCommand::ResetPassword { username, password } => {
+            let user = UserMac::get_by_username(db, &username).await?;
+            let pwd = read_password(password)?;
+            let data = UserPatch {
+                pwd: Some(hash_pwd(&pwd)?),
+                ..Default::default()
+            };
+            UserMac::update(db, user.id, data).await?;
+            let revoked = SessionMac::revoke_all(db, user.id).await?;
+            println!(
+                "User '{}' password reset ({} sessions revoked)",
+                user.username, revoked
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// export - write the groceries of a user (all visible or of a shopping list)
+async fn export(
+    db: &Db,
+    username: &str,
+    list_id: Option<i64>,
+    format: ExportFormat,
+    output: Option<String>,
+) -> Result<(), Error> {
+    let user = UserMac::get_by_username(db, username).await?;
+    let utx = UserCtx { user_id: user.id };
+
+    let groceries = match list_id {
+        Some(list_id) => GroceryMac::list_by_list_id(db, &utx, list_id).await?,
+        None => GroceryMac::list(db, &utx, &GroceryListOptions::default()).await?,
+    };
+
+    let content = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&groceries)? + "\n",
+        ExportFormat::Csv => to_csv(&groceries),
+    };
+
+    match output {
+        Some(file) => fs::write(file, content)?,
+        None => io::stdout().write_all(content.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+// region:    Utils
+/// Read the password from the option, or from stdin
+fn read_password(password: Option<String>) -> Result<String, Error> {
+    let pwd = match password {
+        Some(pwd) => pwd,
+        None => {
+            eprint!("Password: ");
+            let mut pwd = String::new();
+            io::stdin().lock().read_line(&mut pwd)?;
+            pwd.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    if pwd.is_empty() {
+        return Err(Error::EmptyPassword);
+    }
+
+    Ok(pwd)
+}
+
+/// Print the migrations status as a table
+fn print_migrations(statuses: &[MigrationStatus]) {
+    for status in statuses {
+        println!(
+            "{:04}  {:<30}  {:<8}  {}",
+            status.version,
+            status.name,
+            format!("{:?}", status.state).to_lowercase(),
+            status.applied_at.as_deref().unwrap_or("")
+        );
+    }
+}
+
+/// Groceries as csv (with header)
+pub(crate) fn to_csv(groceries: &[Grocery]) -> String {
+    let mut csv = String::from("id,list_id,position,name,quantity,unit,cost,status\n");
+    for grocery in groceries {
+        let fields = [
+            grocery.id.to_string(),
+            grocery.list_id.map(|id| id.to_string()).unwrap_or_default(),
+            grocery.position.to_string(),
+            grocery.name.to_string(),
+            grocery.quantity.0.normalize().to_string(),
+            grocery.unit.clone().unwrap_or_default(),
+            grocery.cost.to_string(),
+            format!("{:?}", grocery.status).to_lowercase(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a csv field if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+// endregion: Utils
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Password cannot be empty.")]
+    EmptyPassword,
+
+    #[error("security.token_key is required (TOKEN_KEY) unless db.recreate.")]
+    MissingTokenKey,
+
+    #[error(transparent)]
+    Model(#[from] model::Error),
+
+    #[error(transparent)]
+    Security(#[from] security::Error),
+
+    #[error(transparent)]
+    Mail(#[from] mail::Error),
+
+    #[error(transparent)]
+    Web(#[from] web::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    IO(#[from] io::Error),
+}
+
+#[cfg(test)]
+#[path = "../_tests/cli.rs"]
+mod tests;
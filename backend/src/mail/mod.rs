@@ -0,0 +1,150 @@
+use crate::config::MailConfig;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rate_limit::RateLimiter;
+use std::path::PathBuf;
+use std::time::Duration;
+
+mod rate_limit;
+mod render;
+
+// re-export
+pub use render::render_shopping_list;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Mailer
+pub struct Mailer {
+    from: Mailbox,
+    transport: MailTransport,
+    limiter: RateLimiter, // emails per user per hour
+}
+
+/// Mail Transport
+enum MailTransport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    File(AsyncFileTransport<Tokio1Executor>),
+}
+
+impl Mailer {
+    /// Create a mailer from the mail config (smtp, or .eml files if `file_dir`)
+    pub fn new(config: &MailConfig) -> Result<Mailer, Error> {
+        if let Some(dir) = &config.file_dir {
+            let mailer = Mailer::new_file(PathBuf::from(dir), &config.from)?;
+            return Ok(mailer.with_rate_limit(config.max_per_hour));
+        }
+
+        let mut builder = if config.smtp_tls {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        };
+        builder = builder.port(config.smtp_port);
+        if let (Some(user), Some(pwd)) = (&config.smtp_user, &config.smtp_pwd) {
+            builder = builder.credentials(Credentials::new(user.to_string(), pwd.to_string()));
+        }
+
+        Ok(Mailer {
+            from: parse_mailbox(&config.from)?,
+            transport: MailTransport::Smtp(builder.build()),
+            limiter: RateLimiter::new(config.max_per_hour, RATE_LIMIT_WINDOW),
+        })
+    }
+
+    /// Create a mailer writing the emails as .eml files in a directory
+    pub fn new_file(dir: PathBuf, from: &str) -> Result<Mailer, Error> {
+        Ok(Mailer {
+            from: parse_mailbox(from)?,
+            transport: MailTransport::File(AsyncFileTransport::new(dir)),
+            limiter: RateLimiter::new(MailConfig::default().max_per_hour, RATE_LIMIT_WINDOW),
+        })
+    }
+
+    /// Set the number of emails a user can send per hour
+    pub fn with_rate_limit(mut self, max_per_hour: u32) -> Mailer {
+        self.limiter = RateLimiter::new(max_per_hour, RATE_LIMIT_WINDOW);
+        self
+    }
+
+    /// Count one more email of the user, fails if the user reached the emails per hour
+    pub fn check_rate_limit(&self, user_id: i64) -> Result<(), Error> {
+        if self.limiter.acquire(user_id) {
+            Ok(())
+        } else {
+            Err(Error::RateLimited(user_id))
+        }
+    }
+
+    /// Send an email with a plain text and a html body
+    pub async fn send(
+        &self,
+        to: &str,
+        subject: &str,
+        text: String,
+        html: String,
+    ) -> Result<(), Error> {
+        let email = self.message(to, subject, text, html)?;
+        self.send_message(email).await
+    }
+
+    /// Build an email with a plain text and a html body (validates the recipient)
+    pub fn message(
+        &self,
+        to: &str,
+        subject: &str,
+        text: String,
+        html: String,
+    ) -> Result<Message, Error> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(parse_mailbox(to)?)
+            .subject(subject)
+            .multipart(MultiPart::alternative_plain_html(text, html))?;
+        Ok(email)
+    }
+
+    /// Send a built email
+    pub async fn send_message(&self, email: Message) -> Result<(), Error> {
+        match &self.transport {
+            MailTransport::Smtp(transport) => {
+                transport.send(email).await?;
+            }
+            MailTransport::File(transport) => {
+                transport.send(email).await?;
+            }
+        }
+        tracing::info!("mail sent");
+
+        Ok(())
+    }
+}
+
+/// Parse an email address (`a@b.c` or `Name <a@b.c>`)
+fn parse_mailbox(address: &str) -> Result<Mailbox, Error> {
+    address
+        .parse::<Mailbox>()
+        .map_err(|_| Error::InvalidAddress(address.to_string()))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid email address '{0}'")]
+    InvalidAddress(String),
+
+    #[error("Too many emails sent by user {0}, retry later")]
+    RateLimited(i64),
+
+    #[error(transparent)]
+    Build(#[from] lettre::error::Error),
+
+    #[error(transparent)]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error(transparent)]
+    File(#[from] lettre::transport::file::Error),
+}
+
+#[cfg(test)]
+#[path = "../_tests/mail.rs"]
+mod tests;
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Rate Limiter - at most `max` per key (e.g. user id) in a fixed time window
+pub(super) struct RateLimiter {
+    max: u32,
+    window: Duration,
+    counts: Mutex<HashMap<i64, (Instant, u32)>>, // key -> (window start, count)
+}
+
+impl RateLimiter {
+    pub(super) fn new(max: u32, window: Duration) -> RateLimiter {
+        RateLimiter {
+            max,
+            window,
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// acquire - count one more for the key, false if the key reached the max of its window
+    pub(super) fn acquire(&self, key: i64) -> bool {
+        let now = Instant::now();
+        let mut counts = self.counts.lock().unwrap_or_else(|ex| ex.into_inner());
+
+        // drop the ended windows (only the keys of the current windows are kept)
+        counts.retain(|_, (start, _)| now.duration_since(*start) < self.window);
+
+        let (_, count) = counts.entry(key).or_insert((now, 0));
+        if *count >= self.max {
+            return false;
+        }
+        *count += 1;
+
+        true
+    }
+}
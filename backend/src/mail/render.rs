@@ -0,0 +1,73 @@
+use crate::model::{Grocery, GroceryStatus, GrocerySummary, ShoppingList};
+
+/// Render a shopping list email as (subject, text, html)
+pub fn render_shopping_list(
+    shopping_list: &ShoppingList,
+    groceries: &[Grocery],
+    summary: &GrocerySummary,
+) -> (String, String, String) {
+    let subject = format!("Shopping list - {}", shopping_list.name);
+
+    // -- text body
+    let mut text = format!("{}\n\n", shopping_list.name);
+    for grocery in groceries {
+        text.push_str(&format!(
+            "[{}] {} - {} x {}\n",
+            status_mark(&grocery.status),
+            grocery.name,
+            quantity_label(grocery),
+            grocery.cost
+        ));
+    }
+    text.push_str(&format!(
+        "\nTotal: {} ({} items)\nIn basket: {}\nOn shelf: {}\n",
+        summary.total_cost, summary.total_count, summary.basket_cost, summary.shelf_cost
+    ));
+
+    // -- html body
+    let mut html = format!("<h1>{}</h1>\n<table>\n", escape_html(&shopping_list.name));
+    html.push_str("<tr><th></th><th>Name</th><th>Quantity</th><th>Unit price</th></tr>\n");
+    for grocery in groceries {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            status_mark(&grocery.status),
+            escape_html(&grocery.name),
+            escape_html(&quantity_label(grocery)),
+            grocery.cost
+        ));
+    }
+    html.push_str("</table>\n");
+    html.push_str(&format!(
+        "<p>Total: {} ({} items)<br>In basket: {}<br>On shelf: {}</p>\n",
+        summary.total_cost, summary.total_count, summary.basket_cost, summary.shelf_cost
+    ));
+
+    (subject, text, html)
+}
+
+/// Quantity with its unit (e.g. `1.5 kg`)
+fn quantity_label(grocery: &Grocery) -> String {
+    let quantity = grocery.quantity.0.normalize();
+    match &grocery.unit {
+        Some(unit) => format!("{} {}", quantity, unit),
+        None => quantity.to_string(),
+    }
+}
+
+/// Status mark (x when in basket)
+fn status_mark(status: &GroceryStatus) -> &'static str {
+    match status {
+        GroceryStatus::Basket => "x",
+        GroceryStatus::Shelf => " ",
+    }
+}
+
+/// Escape the html special characters of a user value
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -1,32 +1,53 @@
 #![allow(unused)] // silence unused warnings while exploring (to comment out)
 
-use model::init_db;
-use std::{env, sync::Arc};
-use web::start_web;
+use clap::Parser;
+use cli::Cli;
+use config::{Config, LogConfig, LogFormat};
+use std::process;
+use tracing_subscriber::EnvFilter;
 
+mod cli;
+mod config;
+mod mail;
 mod model;
 mod security;
 mod web;
 
-const DEFAULT_WEB_FOLDER: &str = "web-folder/";
-const DEFAULT_WEB_PORT: u16 = 8080;
-
 /// main - entry point to the application
 #[tokio::main]
 async fn main() {
-    // compute the web_folder
-    let mut args: Vec<String> = env::args().collect();
-    let web_folder = args.pop().unwrap_or_else(|| DEFAULT_WEB_FOLDER.to_string());
-    let web_port = DEFAULT_WEB_PORT;
-
-    // get the database
-    // TODO - loop until valid DB
-    let db = init_db().await.expect("Cannot init db");
-    let db = Arc::new(db);
-
-    // start the server
-    match start_web(&web_folder, web_port, db).await {
-        Ok(_) => println!("Server ended"),
-        Err(ex) => println!("ERROR - web server failed to start. Cause {:?}", ex),
+    // parse the command line (serve if no command)
+    let cli = Cli::parse();
+
+    // load the config (config.toml and env vars)
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(ex) => {
+            eprintln!("ERROR - {}", ex);
+            process::exit(1);
+        }
+    };
+
+    // log events (to stderr, stdout is kept for the command outputs)
+    init_tracing(&config.log);
+
+    // run the command
+    if let Err(ex) = cli::run(cli, config).await {
+        tracing::error!(error = %ex, "command failed");
+        process::exit(1);
+    }
+}
+
+/// init_tracing - install the global log subscriber (text or json lines)
+fn init_tracing(config: &LogConfig) {
+    // level already validated by the config
+    let filter = EnvFilter::try_new(&config.level).unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+
+    match config.format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().flatten_event(true).init(),
     }
 }
@@ -0,0 +1,301 @@
+use super::db::Db;
+use super::grocery::Grocery;
+use super::list_member::ListMemberMac;
+use crate::{
+    model::{self, FieldError},
+    security::UserCtx,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+
+// percent of the limit from which a budget is near
+const BUDGET_NEAR_PERCENT: i128 = 90;
+const BUDGET_LIMIT_MAX: i64 = 1_000_000_000_000;
+
+// region:    Budget Types
+/// Budget - spending limit settings (no limit when `limit` is None)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Budget {
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub strict: bool, // reject the writes going over the limit
+}
+
+/// Budget Status - total cost against the limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub limit: i64,
+    pub strict: bool,
+    pub total: i64,
+    pub level: BudgetLevel,
+    pub overshoot: i64, // amount over the limit (0 when not over)
+}
+
+/// Budget Level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BudgetLevel {
+    Under,
+    Near,
+    Over,
+}
+
+/// Grocery with the budget status of its shopping list (or user)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroceryWithBudget {
+    #[serde(flatten)]
+    pub grocery: Grocery,
+    pub budget: Option<BudgetStatus>,
+}
+
+/// Budget Scope - groceries of a shopping list, or groceries without list of a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetScope {
+    List(i64),
+    User(i64),
+}
+
+impl BudgetScope {
+    /// scope of a grocery
+    pub fn of(list_id: Option<i64>, cid: i64) -> Self {
+        match list_id {
+            Some(list_id) => BudgetScope::List(list_id),
+            None => BudgetScope::User(cid),
+        }
+    }
+}
+
+/// Budget row (limit settings and total of a scope)
+#[derive(sqlx::FromRow, Debug)]
+struct BudgetRow {
+    budget: Option<i64>,
+    budget_strict: bool,
+    total: i64,
+}
+
+impl BudgetRow {
+    /// status - None when no limit
+    fn status(&self) -> Option<BudgetStatus> {
+        let limit = self.budget?;
+        // i128 to not overflow on the large totals / limits
+        let level = if self.total > limit {
+            BudgetLevel::Over
+        } else if i128::from(self.total) * 100 >= i128::from(limit) * BUDGET_NEAR_PERCENT {
+            BudgetLevel::Near
+        } else {
+            BudgetLevel::Under
+        };
+
+        Some(BudgetStatus {
+            limit,
+            strict: self.budget_strict,
+            total: self.total,
+            level,
+            overshoot: self.total.saturating_sub(limit).max(0),
+        })
+    }
+}
+// endregion: Budget Types
+
+// region:    Budget Validation
+impl Budget {
+    /// validate - check the limit (no limit is valid), fails with the field error
+    pub fn validate(self) -> Result<Budget, model::Error> {
+        let error = match self.limit {
+            Some(limit) if limit < 0 => Some(FieldError::new("limit", "cannot be negative")),
+            Some(limit) if limit > BUDGET_LIMIT_MAX => Some(FieldError::new(
+                "limit",
+                format!("cannot be greater than {}", BUDGET_LIMIT_MAX),
+            )),
+            _ => None,
+        };
+
+        match error {
+            Some(error) => Err(model::Error::Validation(vec![error])),
+            None => Ok(self),
+        }
+    }
+}
+// endregion: Budget Validation
+
+// region:    BudgetMac
+/// Budget Model Access Controller
+pub struct BudgetMac;
+
+impl BudgetMac {
+    const LIST_TABLE: &'static str = "shopping_lists";
+    const USER_TABLE: &'static str = "users";
+}
+
+impl BudgetMac {
+    /// get_for_list - get the budget status of a shopping list (any member)
+    pub async fn get_for_list(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<Option<BudgetStatus>, model::Error> {
+        ListMemberMac::get_role(db, utx, list_id).await?;
+
+        Self::status(db, &BudgetScope::List(list_id)).await
+    }
+
+    /// set_for_list - set the budget of a shopping list (owner only)
+    pub async fn set_for_list(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: i64,
+        data: Budget,
+    ) -> Result<Option<BudgetStatus>, model::Error> {
+        ListMemberMac::require_owner(db, utx, list_id).await?;
+
+        Self::set(db, Self::LIST_TABLE, list_id, data).await?;
+
+        Self::status(db, &BudgetScope::List(list_id)).await
+    }
+
+    /// get_for_user - get the budget status of the user groceries without list
+    pub async fn get_for_user(
+        db: &Db,
+        utx: &UserCtx,
+    ) -> Result<Option<BudgetStatus>, model::Error> {
+        Self::status(db, &BudgetScope::User(utx.user_id)).await
+    }
+
+    /// set_for_user - set the budget of the user groceries without list
+    pub async fn set_for_user(
+        db: &Db,
+        utx: &UserCtx,
+        data: Budget,
+    ) -> Result<Option<BudgetStatus>, model::Error> {
+        Self::set(db, Self::USER_TABLE, utx.user_id, data).await?;
+
+        Self::status(db, &BudgetScope::User(utx.user_id)).await
+    }
+
+    /// with_status - attach the budget status of its scope to a grocery
+    pub async fn with_status(db: &Db, grocery: Grocery) -> Result<GroceryWithBudget, model::Error> {
+        let scope = BudgetScope::of(grocery.list_id, grocery.cid);
+        let budget = Self::status(db, &scope).await?;
+
+        Ok(GroceryWithBudget { grocery, budget })
+    }
+
+    /// status - get the budget status of a scope (None when no limit)
+    pub async fn status(
+        db: &Db,
+        scope: &BudgetScope,
+    ) -> Result<Option<BudgetStatus>, model::Error> {
+        let mut con = db.acquire().await?;
+        let row = Self::fetch_row(&mut con, scope).await?;
+
+        Ok(row.status())
+    }
+}
+
+/// Strict budget checks (within the grocery write transaction)
+impl BudgetMac {
+    /// total - lock the scope and get its current total
+    ///   The scope row stays locked until the end of the transaction, so the concurrent writes
+    ///   of the scope wait, and then read a total with the groceries of this one (read committed).
+    pub(super) async fn total(
+        con: &mut PgConnection,
+        scope: &BudgetScope,
+    ) -> Result<i64, model::Error> {
+        Self::lock_scope(con, scope).await?;
+        let row = Self::fetch_row(con, scope).await?;
+
+        Ok(row.total)
+    }
+
+    /// check_strict - fail if a strict budget is now over its limit and the total increased
+    pub(super) async fn check_strict(
+        con: &mut PgConnection,
+        scope: &BudgetScope,
+        total_before: i64,
+    ) -> Result<(), model::Error> {
+        let row = Self::fetch_row(con, scope).await?;
+
+        match row.budget {
+            Some(limit) if row.budget_strict && row.total > limit && row.total > total_before => {
+                Err(model::Error::BudgetExceeded(limit, row.total))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Utils
+impl BudgetMac {
+    /// set - set the budget columns of a shopping list or user
+    async fn set(db: &Db, table: &'static str, id: i64, data: Budget) -> Result<(), model::Error> {
+        let data = data.validate()?;
+
+        // Note: sqlb skips None values, so raw sql to be able to clear the limit
+        let sql = format!(
+            "UPDATE {} SET budget = $1, budget_strict = $2 WHERE id = $3",
+            table
+        );
+        let result = sqlx::query(&sql)
+            .bind(data.limit)
+            .bind(data.strict)
+            .bind(id)
+            .execute(db)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(model::Error::EntityNotFound(table, id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// lock_scope - lock the shopping list or user row of a scope
+    /// (`FOR NO KEY UPDATE`, does not block the foreign key checks of the rows referencing it)
+    async fn lock_scope(con: &mut PgConnection, scope: &BudgetScope) -> Result<(), model::Error> {
+        let (table, id) = match scope {
+            BudgetScope::List(list_id) => (Self::LIST_TABLE, *list_id),
+            BudgetScope::User(user_id) => (Self::USER_TABLE, *user_id),
+        };
+
+        let sql = format!("SELECT id FROM {} WHERE id = $1 FOR NO KEY UPDATE", table);
+        sqlx::query(&sql).bind(id).fetch_optional(con).await?;
+
+        Ok(())
+    }
+
+    /// fetch_row - get the budget settings and total cost of a scope
+    async fn fetch_row(
+        con: &mut PgConnection,
+        scope: &BudgetScope,
+    ) -> Result<BudgetRow, model::Error> {
+        let (sql, table, id) = match scope {
+            BudgetScope::List(list_id) => (
+                "SELECT budget, budget_strict, \
+                 COALESCE((SELECT ROUND(SUM(quantity * cost)) FROM groceries WHERE list_id = l.id), 0)::bigint AS total \
+                 FROM shopping_lists l WHERE id = $1",
+                Self::LIST_TABLE,
+                *list_id,
+            ),
+            BudgetScope::User(user_id) => (
+                "SELECT budget, budget_strict, \
+                 COALESCE((SELECT ROUND(SUM(quantity * cost)) FROM groceries WHERE list_id IS NULL AND cid = u.id), 0)::bigint AS total \
+                 FROM users u WHERE id = $1",
+                Self::USER_TABLE,
+                *user_id,
+            ),
+        };
+
+        sqlx::query_as::<_, BudgetRow>(sql)
+            .bind(id)
+            .fetch_one(con)
+            .await
+            .map_err(|sqlx_error| match sqlx_error {
+                sqlx::Error::RowNotFound => model::Error::EntityNotFound(table, id.to_string()),
+                other => model::Error::Sqlx(other),
+            })
+    }
+}
+// endregion: BudgetMac
+
+#[cfg(test)]
+#[path = "../_tests/model_budget.rs"]
+mod tests;
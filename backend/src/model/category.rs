@@ -0,0 +1,207 @@
+use super::db::Db;
+use crate::{
+    model::{self, FieldError},
+    security::UserCtx,
+};
+use serde::{Deserialize, Serialize};
+use sqlb::HasFields;
+use sqlx::{Executor, Postgres};
+
+// region:    Category Types
+/// Category - a grocery category of the user (e.g. produce, dairy), mapped to a store aisle
+#[derive(sqlx::FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    pub id: i64,
+    pub cid: i64, // owner id
+    pub name: String,
+    pub position: i64, // walk order in the store (ascending)
+    pub aisle: Option<String>,
+}
+
+/// Category Patch
+#[derive(sqlb::Fields, Default, Debug, Clone, Deserialize)]
+pub struct CategoryPatch {
+    pub name: Option<String>,
+    pub position: Option<i64>,
+    pub aisle: Option<String>,
+}
+// endregion: Category Types
+
+// region:    CategoryPatch Validation
+const NAME_MAX_LEN: usize = 100;
+const AISLE_MAX_LEN: usize = 32;
+
+impl CategoryPatch {
+    /// validate - trim the text values and check all the values (name is required on create,
+    /// and one field at least on update), fails with the list of the field errors
+    pub fn validate(mut self, create: bool) -> Result<CategoryPatch, model::Error> {
+        let mut errors = Vec::new();
+
+        self.name = self.name.map(|name| name.trim().to_string());
+        match &self.name {
+            None if create => errors.push(FieldError::new("name", "is required")),
+            Some(name) if name.is_empty() => {
+                errors.push(FieldError::new("name", "cannot be empty"))
+            }
+            Some(name) if name.chars().count() > NAME_MAX_LEN => errors.push(FieldError::new(
+                "name",
+                format!("cannot be longer than {} characters", NAME_MAX_LEN),
+            )),
+            _ => (),
+        }
+
+        self.aisle = self.aisle.map(|aisle| aisle.trim().to_string());
+        match &self.aisle {
+            Some(aisle) if aisle.is_empty() => {
+                errors.push(FieldError::new("aisle", "cannot be empty"))
+            }
+            Some(aisle) if aisle.chars().count() > AISLE_MAX_LEN => errors.push(FieldError::new(
+                "aisle",
+                format!("cannot be longer than {} characters", AISLE_MAX_LEN),
+            )),
+            _ => (),
+        }
+
+        // an update must set at least one field
+        if !create && self.fields().is_empty() {
+            errors.push(FieldError::new("patch", "has no field to update"));
+        }
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(model::Error::Validation(errors))
+        }
+    }
+}
+// endregion: CategoryPatch Validation
+
+// region:    CategoryMac
+/// Category Model Access Controller
+pub struct CategoryMac;
+
+impl CategoryMac {
+    pub(super) const TABLE: &'static str = "categories";
+    const COLUMNS: &'static [&'static str] = &["id", "cid", "name", "position", "aisle"];
+}
+
+impl CategoryMac {
+    /// create - create a new category of the user
+    pub async fn create(
+        db: &Db,
+        utx: &UserCtx,
+        data: CategoryPatch,
+    ) -> Result<Category, model::Error> {
+        let data = data.validate(true)?;
+
+        let mut fields = data.fields();
+        fields.push(("cid", utx.user_id).into());
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(fields)
+            .returning(Self::COLUMNS);
+
+        let category = sb.fetch_one(db).await?;
+
+        Ok(category)
+    }
+
+    /// get - get a category (owner only), on the pool or on the connection of a transaction
+    pub async fn get<'e, E>(db: E, utx: &UserCtx, id: i64) -> Result<Category, model::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("id", id)
+            .and_where_eq("cid", utx.user_id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+
+    /// list - get the categories of the user, in the store aisles order
+    pub async fn list(db: &Db, utx: &UserCtx) -> Result<Vec<Category>, model::Error> {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("cid", utx.user_id)
+            .order_bys(&["position", "id"]);
+
+        // execute the query
+        let categories = sb.fetch_all(db).await?;
+
+        Ok(categories)
+    }
+
+    /// list_by_ids - get the categories of visible groceries (any owner, e.g. on a shared list)
+    pub(super) async fn list_by_ids(db: &Db, ids: &[i64]) -> Result<Vec<Category>, model::Error> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE id = ANY($1)",
+            Self::COLUMNS.join(", "),
+            Self::TABLE
+        );
+
+        let categories = sqlx::query_as::<_, Category>(&sql)
+            .bind(ids)
+            .fetch_all(db)
+            .await?;
+
+        Ok(categories)
+    }
+
+    /// update - update a category (owner only)
+    pub async fn update(
+        db: &Db,
+        utx: &UserCtx,
+        id: i64,
+        data: CategoryPatch,
+    ) -> Result<Category, model::Error> {
+        let data = data.validate(false)?;
+
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(data.fields())
+            .and_where_eq("id", id)
+            .and_where_eq("cid", utx.user_id)
+            .returning(Self::COLUMNS);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+
+    /// delete - delete a category (owner only), its groceries become uncategorized
+    pub async fn delete(db: &Db, utx: &UserCtx, id: i64) -> Result<Category, model::Error> {
+        let sb = sqlb::delete()
+            .table(Self::TABLE)
+            .returning(Self::COLUMNS)
+            .and_where_eq("id", id)
+            .and_where_eq("cid", utx.user_id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+}
+// endregion: CategoryMac
+
+// region:    Utils
+/// handle_fetch_one_result - handle sqlx::Error
+fn handle_fetch_one_result(
+    result: Result<Category, sqlx::Error>,
+    typ: &'static str,
+    id: i64,
+) -> Result<Category, model::Error> {
+    result.map_err(|sqlx_error| match sqlx_error {
+        sqlx::Error::RowNotFound => model::Error::EntityNotFound(typ, id.to_string()),
+        other => model::Error::Sqlx(other),
+    })
+}
+// endregion: Utils
+
+#[cfg(test)]
+#[path = "../_tests/model_category.rs"]
+mod tests;
@@ -1,87 +1,182 @@
+use super::migration::{
+    check_applied, load_expected, migrate, revert_last, status, ExpectedMigration, MigrationStatus,
+};
+use super::sql_script::exec_script;
+use crate::config::DbConfig;
+use crate::model;
+use rand::Rng;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use std::fs;
-use std::path::PathBuf;
-use std::time::Duration;
-
-const PG_HOST: &str = "localhost";
-const PG_ROOT_DB: &str = "postgres";
-const PG_ROOT_USER: &str = "postgres";
-const PG_ROOT_PWD: &str = "postgres";
-// app db
-const PG_APP_DB: &str = "app_db";
-const PG_APP_USER: &str = "app_user";
-const PG_APP_PWD: &str = "password";
-const PG_APP_MAX_CON: u32 = 5;
+use std::time::{Duration, Instant};
+
 // sql files
-const SQL_DIR: &str = "sql/";
-const SQL_RECREATE: &str = "sql/00-recreate-db.sql";
+const SQL_MIGRATIONS_DIR: &str = "sql/migrations/";
+const SQL_RECREATE: &str = "sql/00-recreate-db.sql"; // creates app_db / app_user of the default db.url
+const SQL_DEV_SEED: &str = "sql/dev-seed.sql";
 
 pub type Db = Pool<Postgres>;
 
-/// Initialize the db
-pub async fn init_db() -> Result<Db, sqlx::Error> {
-    // -- Create the db with PG_ROOT (dev only)
-    {
-        let root_db = new_db_pool(PG_HOST, PG_ROOT_DB, PG_ROOT_USER, PG_ROOT_PWD, 1).await?;
-        pexec(&root_db, SQL_RECREATE).await?;
+/// Initialize the db (apply the pending migrations)
+pub async fn init_db(config: &DbConfig) -> Result<Db, model::Error> {
+    let recreate = recreate_opt_in(config);
+
+    // -- Recreate the db with the root user (dev only, opt-in)
+    if recreate {
+        let root_db = new_db_pool(config, &config.root_url, 1).await?;
+        pexec_no_tx(&root_db, SQL_RECREATE).await?;
+        root_db.close().await;
     }
 
-    // -- Run the app sql files
-    let app_db = new_db_pool(PG_HOST, PG_APP_DB, PG_APP_USER, PG_APP_PWD, 1).await?;
-    let mut paths: Vec<PathBuf> = fs::read_dir(SQL_DIR)?
-        .filter_map(|e| e.ok().map(|e| e.path()))
-        .collect();
-    paths.sort();
-    // execute each file
-    for path in paths {
-        if let Some(path) = path.to_str() {
-            // only .sql and not the recreate
-            if path.ends_with(".sql") && path != SQL_RECREATE {
-                pexec(&app_db, path).await?;
-            }
+    // -- Apply the pending migrations (and the dev seed on a recreated db)
+    {
+        let app_db = new_db_pool(config, &config.url, 1).await?;
+        migrate(&app_db, SQL_MIGRATIONS_DIR).await?;
+        if recreate {
+            pexec(&app_db, SQL_DEV_SEED).await?;
         }
+        app_db.close().await;
     }
 
     // returning the app db
-    new_db_pool(PG_HOST, PG_APP_DB, PG_APP_USER, PG_APP_PWD, PG_APP_MAX_CON).await
+    let app_db = new_db_pool(config, &config.url, config.max_connections).await?;
+    Ok(app_db)
 }
 
-/// Execute a sql file
-async fn pexec(db: &Db, file: &str) -> Result<(), sqlx::Error> {
-    // Read the file
+/// Connect to the app db (no migration)
+pub async fn connect_db(config: &DbConfig) -> Result<Db, model::Error> {
+    let db = new_db_pool(config, &config.url, config.max_connections).await?;
+    Ok(db)
+}
+
+/// Apply the pending migrations
+pub async fn migrate_up(db: &Db) -> Result<(), model::Error> {
+    migrate(db, SQL_MIGRATIONS_DIR).await
+}
+
+/// Revert the last applied migration (None if none applied)
+pub async fn migrate_down(db: &Db) -> Result<Option<MigrationStatus>, model::Error> {
+    revert_last(db, SQL_MIGRATIONS_DIR).await
+}
+
+/// Get the status of the migrations
+pub async fn migrate_status(db: &Db) -> Result<Vec<MigrationStatus>, model::Error> {
+    status(db, SQL_MIGRATIONS_DIR).await
+}
+
+/// Get the expected migrations (the migration files, to load once at startup)
+pub fn expected_migrations() -> Result<Vec<ExpectedMigration>, model::Error> {
+    load_expected(SQL_MIGRATIONS_DIR)
+}
+
+/// Check the db is ready to serve (reachable, and every expected migration applied),
+/// read only, returns the current migration version
+pub async fn check_ready(
+    db: &Db,
+    expected: &[ExpectedMigration],
+) -> Result<Option<i64>, model::Error> {
+    sqlx::query("SELECT 1").execute(db).await?;
+
+    check_applied(db, expected).await
+}
+
+/// Insert the dev seed data
+pub async fn seed_db(db: &Db) -> Result<(), model::Error> {
+    pexec(db, SQL_DEV_SEED).await
+}
+
+/// Recreate opt-in (`db.recreate`), always on for unit tests
+fn recreate_opt_in(config: &DbConfig) -> bool {
+    cfg!(test) || config.recreate
+}
+
+/// Execute a sql file in one transaction
+async fn pexec(db: &Db, file: &str) -> Result<(), model::Error> {
+    let content = read_sql_file(file)?;
+
+    let mut tx = db.begin().await?;
+    exec_script(&mut tx, file, &content).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Execute a sql file without transaction (for statements like `DROP DATABASE`)
+async fn pexec_no_tx(db: &Db, file: &str) -> Result<(), model::Error> {
+    let content = read_sql_file(file)?;
+
+    let mut con = db.acquire().await?;
+    exec_script(&mut con, file, &content).await
+}
+
+/// Read a sql file
+fn read_sql_file(file: &str) -> Result<String, model::Error> {
     let content = fs::read_to_string(file).map_err(|ex| {
-        println!("ERROR reading {} (cause: {:?})", file, ex);
+        tracing::error!(file, error = %ex, "cannot read sql file");
         ex
     })?;
 
-    // TODO: Make the split more sql proof
-    let sqls: Vec<&str> = content.split(";").collect();
+    Ok(content)
+}
 
-    for sql in sqls {
-        match sqlx::query(sql).execute(db).await {
-            Ok(_) => (),
-            Err(ex) => println!("WARNING - pexec - Sql file '{}' FAILED cause: {}", file, ex),
+/// Create a new db pool (retry with exponential backoff while the db is not reachable,
+/// at most `connect_retries` times and `connect_max_wait_ms` in total)
+async fn new_db_pool(config: &DbConfig, url: &str, max_con: u32) -> Result<Db, sqlx::Error> {
+    let deadline = Instant::now() + Duration::from_millis(config.connect_max_wait_ms);
+    let mut attempt = 0;
+    loop {
+        let result = PgPoolOptions::new()
+            .max_connections(max_con)
+            .connect_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .connect(url)
+            .await;
+
+        match result {
+            Err(ex) if attempt < config.connect_retries && is_transient(&ex) => {
+                let delay = backoff_delay(config, attempt);
+                // no time left for a retry
+                if Instant::now() + delay > deadline {
+                    tracing::error!(
+                        max_wait_ms = config.connect_max_wait_ms,
+                        error = %ex,
+                        "db not reachable, max wait reached"
+                    );
+                    return Err(ex);
+                }
+                tracing::warn!(
+                    retry = attempt + 1,
+                    retries = config.connect_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %ex,
+                    "db not reachable, retrying"
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
         }
     }
+}
 
-    Ok(())
+/// Delay before a retry, exponential (capped) with jitter (between half and full delay)
+fn backoff_delay(config: &DbConfig, attempt: u32) -> Duration {
+    let delay = config
+        .connect_backoff_ms
+        .saturating_mul(2u64.saturating_pow(attempt))
+        .min(config.connect_backoff_max_ms);
+    let jittered = rand::thread_rng().gen_range(delay / 2..=delay);
+
+    Duration::from_millis(jittered)
 }
 
-/// Create a new db pool
-async fn new_db_pool(
-    host: &str,
-    db: &str,
-    user: &str,
-    pwd: &str,
-    max_con: u32,
-) -> Result<Db, sqlx::Error> {
-    let con_string = format!("postgres://{}:{}@{}/{}", user, pwd, host, db);
-    PgPoolOptions::new()
-        .max_connections(max_con)
-        .connect_timeout(Duration::from_millis(500))
-        .connect(&con_string)
-        .await
+/// Errors worth a retry (db not reachable yet, or still starting)
+fn is_transient(ex: &sqlx::Error) -> bool {
+    match ex {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        // 57P03 - cannot_connect_now (the database system is starting up)
+        sqlx::Error::Database(db_ex) => db_ex.code().as_deref() == Some("57P03"),
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,18 @@
+use super::budget::{BudgetMac, BudgetScope};
+use super::category::{Category, CategoryMac};
 use super::db::Db;
-use crate::{model, security::UserCtx};
+use super::list_member::ListMemberMac;
+use crate::{
+    model::{self, FieldError},
+    security::UserCtx,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sqlb::HasFields;
+use sqlx::PgConnection;
 
 // region:    Grocery Types
 /// Grocery
@@ -9,17 +20,128 @@ use sqlb::HasFields;
 pub struct Grocery {
     pub id: i64,
     pub cid: i64, // creator id
-    pub cost: i64,
+    pub list_id: Option<i64>,
+    pub position: i64, // user order (ascending)
+    pub quantity: Quantity,
+    pub unit: Option<String>,
+    pub cost: i64, // unit price
     pub name: String,
     pub status: GroceryStatus,
+    pub category_id: Option<i64>,
 }
 
 /// Grocery Patch
 #[derive(sqlb::Fields, Default, Debug, Clone, Deserialize)]
 pub struct GroceryPatch {
+    pub list_id: Option<i64>,
+    pub quantity: Option<Quantity>,
+    pub unit: Option<String>,
     pub cost: Option<i64>,
     pub name: Option<String>,
     pub status: Option<GroceryStatus>,
+    pub category_id: Option<i64>,
+}
+
+/// Grocery Operation - one create, update or delete of a batch
+///   (e.g. `{"op": "update", "id": 100, "data": {"status": "Basket"}}`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum GroceryOp {
+    Create { data: GroceryPatch },
+    Update { id: i64, data: GroceryPatch },
+    Delete { id: i64 },
+}
+
+/// Grocery Operation Kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroceryOpKind {
+    Create,
+    Update,
+    Delete,
+}
+
+/// Grocery Operation Result - the grocery created, updated or deleted by the operation `index`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroceryOpResult {
+    pub index: usize,
+    pub op: GroceryOpKind,
+    pub grocery: Grocery,
+}
+
+impl GroceryOp {
+    pub fn kind(&self) -> GroceryOpKind {
+        match self {
+            GroceryOp::Create { .. } => GroceryOpKind::Create,
+            GroceryOp::Update { .. } => GroceryOpKind::Update,
+            GroceryOp::Delete { .. } => GroceryOpKind::Delete,
+        }
+    }
+}
+
+/// Grocery List Options - filters, sort and page (e.g. `?status=shelf&sort=name&limit=20`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GroceryListOptions {
+    pub status: Option<GroceryStatus>,
+    pub name: Option<String>, // name contains (case insensitive)
+    pub cost_min: Option<i64>,
+    pub cost_max: Option<i64>,
+    pub sort: GrocerySort,
+    pub limit: Option<i64>,     // page size (all the groceries if none)
+    pub cursor: Option<String>, // `next` cursor of the previous page
+    #[serde(skip)]
+    pub by_category: bool, // grouped by category, in the store aisles order (uncategorized last)
+}
+
+/// Grocery Sort - ascending, then newest first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrocerySort {
+    Name,
+    Cost,
+    #[default]
+    Position,
+    Created, // newest first
+}
+
+/// Grocery Cursor - sort key and id of the last grocery of a page
+#[derive(Debug, Serialize, Deserialize)]
+struct GroceryCursor {
+    sort: GrocerySort,
+    key: Value,
+    id: i64,
+}
+
+/// Grocery Group - the groceries of a category (none for the uncategorized groceries)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroceryGroup {
+    pub category: Option<Category>,
+    pub groceries: Vec<Grocery>,
+}
+
+/// Grocery Quantity - decimal amount of `unit` (e.g. 1.5 kg), defaults to 1
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[sqlx(transparent)]
+#[serde(transparent)]
+pub struct Quantity(pub Decimal);
+sqlb::bindable!(Quantity);
+
+impl Default for Quantity {
+    fn default() -> Self {
+        Quantity(Decimal::ONE)
+    }
+}
+
+/// Grocery Summary - costs (quantity x unit price) and counts of groceries
+#[derive(sqlx::FromRow, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrocerySummary {
+    pub total_cost: i64,
+    pub basket_cost: i64,
+    pub shelf_cost: i64, // remaining to pick up
+    pub total_count: i64,
+    pub basket_count: i64,
+    pub shelf_count: i64,
 }
 
 /// Grocery Status
@@ -27,58 +149,366 @@ pub struct GroceryPatch {
 #[sqlx(type_name = "grocery_status_enum")]
 #[sqlx(rename_all = "lowercase")]
 pub enum GroceryStatus {
+    #[serde(alias = "shelf")]
     Shelf,
+    #[serde(alias = "basket")]
     Basket,
 }
 sqlb::bindable!(GroceryStatus);
 // endregion: Grocery Types
 
+// region:    GroceryPatch Validation
+const NAME_MAX_LEN: usize = 200;
+const UNIT_MAX_LEN: usize = 32;
+const COST_MAX: i64 = 1_000_000_000;
+const QUANTITY_MAX: i64 = 1_000_000;
+const QUANTITY_MAX_DECIMALS: u32 = 3; // numeric(12, 3)
+
+impl GroceryPatch {
+    /// validate - trim the text values and check all the values (name is required on create,
+    /// and one field at least on update), fails with the list of the field errors
+    pub fn validate(mut self, create: bool) -> Result<GroceryPatch, model::Error> {
+        let mut errors = Vec::new();
+
+        self.name = self.name.map(|name| name.trim().to_string());
+        match &self.name {
+            None if create => errors.push(FieldError::new("name", "is required")),
+            Some(name) if name.is_empty() => {
+                errors.push(FieldError::new("name", "cannot be empty"))
+            }
+            Some(name) if name.chars().count() > NAME_MAX_LEN => errors.push(FieldError::new(
+                "name",
+                format!("cannot be longer than {} characters", NAME_MAX_LEN),
+            )),
+            _ => (),
+        }
+
+        if let Some(cost) = self.cost {
+            if cost < 0 {
+                errors.push(FieldError::new("cost", "cannot be negative"));
+            } else if cost > COST_MAX {
+                errors.push(FieldError::new(
+                    "cost",
+                    format!("cannot be greater than {}", COST_MAX),
+                ));
+            }
+        }
+
+        if let Some(Quantity(quantity)) = self.quantity {
+            if quantity <= Decimal::ZERO {
+                errors.push(FieldError::new("quantity", "must be positive"));
+            } else if quantity > Decimal::from(QUANTITY_MAX) {
+                errors.push(FieldError::new(
+                    "quantity",
+                    format!("cannot be greater than {}", QUANTITY_MAX),
+                ));
+            } else if quantity.normalize().scale() > QUANTITY_MAX_DECIMALS {
+                errors.push(FieldError::new(
+                    "quantity",
+                    format!("cannot have more than {} decimals", QUANTITY_MAX_DECIMALS),
+                ));
+            }
+        }
+
+        self.unit = self.unit.map(|unit| unit.trim().to_string());
+        match &self.unit {
+            Some(unit) if unit.is_empty() => {
+                errors.push(FieldError::new("unit", "cannot be empty"))
+            }
+            Some(unit) if unit.chars().count() > UNIT_MAX_LEN => errors.push(FieldError::new(
+                "unit",
+                format!("cannot be longer than {} characters", UNIT_MAX_LEN),
+            )),
+            _ => (),
+        }
+
+        // an update must set at least one field
+        if !create && self.fields().is_empty() {
+            errors.push(FieldError::new("patch", "has no field to update"));
+        }
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(model::Error::Validation(errors))
+        }
+    }
+}
+// endregion: GroceryPatch Validation
+
+// region:    GroceryListOptions
+const BATCH_OPS_MAX: usize = 100;
+const LIST_LIMIT_MAX: i64 = 500;
+const SEARCH_QUERY_MAX_LEN: usize = 200;
+const SEARCH_LIMIT_DEFAULT: i64 = 20;
+const SEARCH_LIMIT_MAX: i64 = 100;
+// word similarity of the fuzzy matches (pg_trgm default 0.6 misses e.g. 'yogurt' for 'yoghurt')
+const SEARCH_SIMILARITY_MIN: f64 = 0.4;
+
+impl GroceryListOptions {
+    /// validate - check the values, and decode the cursor
+    fn validate(&self) -> Result<Option<GroceryCursor>, model::Error> {
+        let mut errors = Vec::new();
+
+        if let Some(limit) = self.limit {
+            if !(1..=LIST_LIMIT_MAX).contains(&limit) {
+                errors.push(FieldError::new(
+                    "limit",
+                    format!("must be between 1 and {}", LIST_LIMIT_MAX),
+                ));
+            }
+        }
+
+        if let (Some(cost_min), Some(cost_max)) = (self.cost_min, self.cost_max) {
+            if cost_min > cost_max {
+                errors.push(FieldError::new(
+                    "cost_min",
+                    "cannot be greater than cost_max",
+                ));
+            }
+        }
+
+        let cursor = match &self.cursor {
+            Some(_) if self.by_category => {
+                errors.push(FieldError::new(
+                    "cursor",
+                    "cannot be used with the groups by category",
+                ));
+                None
+            }
+            Some(cursor) => match decode_cursor(cursor) {
+                Some(cursor) if cursor.sort == self.sort => Some(cursor),
+                Some(_) => {
+                    errors.push(FieldError::new("cursor", "does not match the sort"));
+                    None
+                }
+                None => {
+                    errors.push(FieldError::new("cursor", "is invalid"));
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if errors.is_empty() {
+            Ok(cursor)
+        } else {
+            Err(model::Error::Validation(errors))
+        }
+    }
+
+    /// next_cursor - cursor of the page after `groceries` (none on the last page, or without limit)
+    pub fn next_cursor(&self, groceries: &[Grocery]) -> Option<String> {
+        let limit = self.limit?;
+        if (groceries.len() as i64) < limit {
+            return None;
+        }
+        let last = groceries.last()?;
+
+        let key = match self.sort {
+            GrocerySort::Name => json!(last.name),
+            GrocerySort::Cost => json!(last.cost),
+            GrocerySort::Position => json!(last.position),
+            GrocerySort::Created => Value::Null,
+        };
+        let cursor = GroceryCursor {
+            sort: self.sort,
+            key,
+            id: last.id,
+        };
+
+        serde_json::to_vec(&cursor)
+            .ok()
+            .map(|cursor| URL_SAFE_NO_PAD.encode(cursor))
+    }
+}
+// endregion: GroceryListOptions
+
 // region:    GroceryMac
 /// Grocery Model Access Controller
 pub struct GroceryMac;
 
 impl GroceryMac {
     const TABLE: &'static str = "groceries";
-    const COLUMNS: &'static [&'static str] = &["id", "cid", "cost", "name", "status"];
+    const COLUMNS: &'static [&'static str] = &[
+        "id",
+        "cid",
+        "list_id",
+        "position",
+        "quantity",
+        "unit",
+        "cost",
+        "name",
+        "status",
+        "category_id",
+    ];
 }
 
 impl GroceryMac {
     /// create - create a new grocery
     pub async fn create(
         db: &Db,
-        _utx: &UserCtx,
+        utx: &UserCtx,
         data: GroceryPatch,
     ) -> Result<Grocery, model::Error> {
-        let mut fields = data.fields();
-        fields.push(("cid", 123).into());
-        let sb = sqlb::insert()
-            .table(Self::TABLE)
-            .data(fields)
-            .returning(Self::COLUMNS);
-
-        let grocery = sb.fetch_one(db).await?;
+        let mut tx = db.begin().await?;
+        let data = Self::authorize_create(&mut tx, utx, data).await?;
+        let grocery = Self::insert(&mut tx, utx, data).await?;
+        tx.commit().await?;
 
         Ok(grocery)
     }
 
     /// get - get a grocery
-    pub async fn get(db: &Db, _utx: &UserCtx, id: i64) -> Result<Grocery, model::Error> {
-        let sb = sqlb::select()
-            .table(Self::TABLE)
-            .columns(Self::COLUMNS)
-            .and_where_eq("id", id);
+    pub async fn get(db: &Db, utx: &UserCtx, id: i64) -> Result<Grocery, model::Error> {
+        let mut con = db.acquire().await?;
+        Self::get_authorized(&mut con, utx, id, false).await
+    }
 
-        let result = sb.fetch_one(db).await;
+    /// list - get list of groceries (own groceries and shopping lists groceries),
+    ///   filtered, sorted and paged by the options (see `GroceryListOptions::next_cursor`)
+    /// Note: sqlb does not support OR / sub-selects / LIMIT, so raw sql with numbered args
+    pub async fn list(
+        db: &Db,
+        utx: &UserCtx,
+        options: &GroceryListOptions,
+    ) -> Result<Vec<Grocery>, model::Error> {
+        let cursor = options.validate()?;
 
-        handle_fetch_one_result(result, Self::TABLE, id)
+        // -- filters ($1 is the user of the visible filter)
+        let mut args = vec![ListArg::Int(utx.user_id)];
+        let mut wheres = vec![sql_where_visible()];
+        if let Some(status) = &options.status {
+            let arg = push_arg(&mut args, ListArg::Status(status.clone()));
+            wheres.push(format!("status = {}", arg));
+        }
+        if let Some(name) = options.name.as_deref().map(str::trim) {
+            if !name.is_empty() {
+                let arg = push_arg(&mut args, ListArg::Text(like_contains(name)));
+                wheres.push(format!("name ILIKE {}", arg));
+            }
+        }
+        if let Some(cost_min) = options.cost_min {
+            let arg = push_arg(&mut args, ListArg::Int(cost_min));
+            wheres.push(format!("cost >= {}", arg));
+        }
+        if let Some(cost_max) = options.cost_max {
+            let arg = push_arg(&mut args, ListArg::Int(cost_max));
+            wheres.push(format!("cost <= {}", arg));
+        }
+
+        // -- page start (after the cursor, in the sort order)
+        if let Some(cursor) = cursor {
+            let key = match (options.sort, cursor.key) {
+                (GrocerySort::Created, _) => None,
+                (GrocerySort::Name, Value::String(name)) => Some(ListArg::Text(name)),
+                (_, key) => match key.as_i64() {
+                    Some(key) => Some(ListArg::Int(key)),
+                    None => {
+                        let error = FieldError::new("cursor", "is invalid");
+                        return Err(model::Error::Validation(vec![error]));
+                    }
+                },
+            };
+            let id = push_arg(&mut args, ListArg::Int(cursor.id));
+            match key {
+                Some(key) => {
+                    let column = sort_column(options.sort);
+                    let key = push_arg(&mut args, key);
+                    wheres.push(format!(
+                        "({column} > {key} OR ({column} = {key} AND id < {id}))"
+                    ));
+                }
+                None => wheres.push(format!("id < {}", id)),
+            }
+        }
+
+        // -- order (by category: category walk order first, with a sub-select
+        //    as the visible filter is unqualified)
+        let mut order_by = Vec::new();
+        if options.by_category {
+            order_by.push(format!(
+                "(SELECT position FROM {} WHERE id = category_id) NULLS LAST, category_id",
+                CategoryMac::TABLE
+            ));
+        }
+        if options.sort != GrocerySort::Created {
+            order_by.push(sort_column(options.sort).to_string());
+        }
+        order_by.push("id DESC".to_string());
+
+        let mut sql = format!(
+            "SELECT {} FROM {} WHERE {} ORDER BY {}",
+            Self::COLUMNS.join(", "),
+            Self::TABLE,
+            wheres.join(" AND "),
+            order_by.join(", ")
+        );
+        if let Some(limit) = options.limit {
+            let arg = push_arg(&mut args, ListArg::Int(limit));
+            sql.push_str(&format!(" LIMIT {}", arg));
+        }
+
+        // execute the query
+        let mut query = sqlx::query_as::<_, Grocery>(&sql);
+        for arg in args {
+            query = match arg {
+                ListArg::Int(value) => query.bind(value),
+                ListArg::Text(value) => query.bind(value),
+                ListArg::Status(value) => query.bind(value),
+            };
+        }
+        let grocery = query.fetch_all(db).await?;
+
+        Ok(grocery)
+    }
+
+    /// list_grouped - get list of groceries grouped by category, in the store aisles order
+    pub async fn list_grouped(db: &Db, utx: &UserCtx) -> Result<Vec<GroceryGroup>, model::Error> {
+        let options = GroceryListOptions {
+            by_category: true,
+            ..Default::default()
+        };
+        let groceries = Self::list(db, utx, &options).await?;
+
+        let mut category_ids: Vec<i64> = groceries.iter().filter_map(|g| g.category_id).collect();
+        category_ids.dedup();
+        let categories = CategoryMac::list_by_ids(db, &category_ids).await?;
+
+        // groceries come ordered by category, so each group is a run of the same category
+        let mut groups: Vec<GroceryGroup> = Vec::new();
+        for grocery in groceries {
+            match groups.last_mut() {
+                Some(group) if group.category.as_ref().map(|c| c.id) == grocery.category_id => {
+                    group.groceries.push(grocery)
+                }
+                _ => groups.push(GroceryGroup {
+                    category: categories
+                        .iter()
+                        .find(|c| Some(c.id) == grocery.category_id)
+                        .cloned(),
+                    groceries: vec![grocery],
+                }),
+            }
+        }
+
+        Ok(groups)
     }
 
-    /// list - get list of groceries
-    pub async fn list(db: &Db, _utx: &UserCtx) -> Result<Vec<Grocery>, model::Error> {
+    /// list_by_list_id - get list of groceries of a shopping list
+    pub async fn list_by_list_id(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<Vec<Grocery>, model::Error> {
+        // the user must be a member of the shopping list
+        ListMemberMac::get_role(db, utx, list_id).await?;
+
         let sb = sqlb::select()
             .table(Self::TABLE)
             .columns(Self::COLUMNS)
-            .order_by("!id");
+            .and_where_eq("list_id", list_id)
+            .order_bys(&["position", "!id"]);
 
         // execute the query
         let grocery = sb.fetch_all(db).await?;
@@ -86,39 +516,457 @@ impl GroceryMac {
         Ok(grocery)
     }
 
+    /// search - get the groceries the user can see matching `query`, best match first
+    ///   - full-text on the name words (stemmed, e.g. 'apples' for 'apple')
+    ///   - trigram word similarity on the name (typos and spelling variants, e.g. 'yogurt')
+    pub async fn search(
+        db: &Db,
+        utx: &UserCtx,
+        query: &str,
+        limit: Option<i64>,
+    ) -> Result<Vec<Grocery>, model::Error> {
+        let query = query.trim();
+        let limit = limit.unwrap_or(SEARCH_LIMIT_DEFAULT);
+        let mut errors = Vec::new();
+        if query.is_empty() {
+            errors.push(FieldError::new("q", "is required"));
+        } else if query.chars().count() > SEARCH_QUERY_MAX_LEN {
+            errors.push(FieldError::new(
+                "q",
+                format!("cannot be longer than {} characters", SEARCH_QUERY_MAX_LEN),
+            ));
+        }
+        if !(1..=SEARCH_LIMIT_MAX).contains(&limit) {
+            errors.push(FieldError::new(
+                "limit",
+                format!("must be between 1 and {}", SEARCH_LIMIT_MAX),
+            ));
+        }
+        if !errors.is_empty() {
+            return Err(model::Error::Validation(errors));
+        }
+
+        // the `<%` operator (trigram index) uses the threshold of the transaction
+        let mut tx = db.begin().await?;
+        sqlx::query(&format!(
+            "SET LOCAL pg_trgm.word_similarity_threshold = {}",
+            SEARCH_SIMILARITY_MIN
+        ))
+        .execute(&mut tx)
+        .await?;
+
+        let sql = format!(
+            "SELECT {} FROM {} \
+             WHERE {} AND (search @@ websearch_to_tsquery('english', $2) OR $2 <% name) \
+             ORDER BY ts_rank(search, websearch_to_tsquery('english', $2)) \
+               + word_similarity($2, name) DESC, id DESC \
+             LIMIT $3",
+            Self::COLUMNS.join(", "),
+            Self::TABLE,
+            sql_where_visible()
+        );
+        let groceries = sqlx::query_as::<_, Grocery>(&sql)
+            .bind(utx.user_id)
+            .bind(query)
+            .bind(limit)
+            .fetch_all(&mut tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(groceries)
+    }
+
+    /// summary - get the costs and counts of the groceries (of a shopping list if `list_id`)
+    pub async fn summary(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: Option<i64>,
+    ) -> Result<GrocerySummary, model::Error> {
+        let select = "SELECT \
+            COALESCE(ROUND(SUM(quantity * cost)), 0)::bigint AS total_cost, \
+            COALESCE(ROUND(SUM(quantity * cost) FILTER (WHERE status = 'basket')), 0)::bigint AS basket_cost, \
+            COALESCE(ROUND(SUM(quantity * cost) FILTER (WHERE status = 'shelf')), 0)::bigint AS shelf_cost, \
+            COUNT(*) AS total_count, \
+            COUNT(*) FILTER (WHERE status = 'basket') AS basket_count, \
+            COUNT(*) FILTER (WHERE status = 'shelf') AS shelf_count";
+
+        let summary = match list_id {
+            Some(list_id) => {
+                // the user must be a member of the shopping list
+                ListMemberMac::get_role(db, utx, list_id).await?;
+                let sql = format!("{} FROM {} WHERE list_id = $1", select, Self::TABLE);
+                sqlx::query_as::<_, GrocerySummary>(&sql)
+                    .bind(list_id)
+                    .fetch_one(db)
+                    .await?
+            }
+            None => {
+                let sql = format!(
+                    "{} FROM {} WHERE {}",
+                    select,
+                    Self::TABLE,
+                    sql_where_visible()
+                );
+                sqlx::query_as::<_, GrocerySummary>(&sql)
+                    .bind(utx.user_id)
+                    .fetch_one(db)
+                    .await?
+            }
+        };
+
+        Ok(summary)
+    }
+
     /// update - update a grocery
     pub async fn update(
         db: &Db,
-        _utx: &UserCtx,
+        utx: &UserCtx,
         id: i64,
         data: GroceryPatch,
     ) -> Result<Grocery, model::Error> {
+        let mut tx = db.begin().await?;
+        let (grocery, data) = Self::authorize_update(&mut tx, utx, id, data).await?;
+        let grocery = Self::update_checked(&mut tx, &grocery, data).await?;
+        tx.commit().await?;
+
+        Ok(grocery)
+    }
+
+    /// reorder - reorder groceries in the given id order
+    ///   Only the positions of the given groceries are exchanged, so groceries
+    ///   created concurrently keep their place, and ids deleted meanwhile are skipped.
+    pub async fn reorder(
+        db: &Db,
+        utx: &UserCtx,
+        ids: Vec<i64>,
+    ) -> Result<Vec<Grocery>, model::Error> {
+        let mut tx = db.begin().await?;
+
+        // lock the groceries still existing, and visible to the user (never the others' rows)
+        let sql = format!(
+            "SELECT {} FROM {} WHERE id = ANY($2) AND {} FOR UPDATE",
+            Self::COLUMNS.join(", "),
+            Self::TABLE,
+            sql_where_visible()
+        );
+        let locked = sqlx::query_as::<_, Grocery>(&sql)
+            .bind(utx.user_id)
+            .bind(&ids)
+            .fetch_all(&mut tx)
+            .await?;
+
+        // the other existing groceries are not found for the user
+        let locked_ids: Vec<i64> = locked.iter().map(|g| g.id).collect();
+        let sql = format!(
+            "SELECT id FROM {} WHERE id = ANY($1) AND NOT (id = ANY($2)) LIMIT 1",
+            Self::TABLE
+        );
+        let hidden = sqlx::query_as::<_, (i64,)>(&sql)
+            .bind(&ids)
+            .bind(&locked_ids)
+            .fetch_optional(&mut tx)
+            .await?;
+        if let Some((id,)) = hidden {
+            return Err(model::Error::EntityNotFound(Self::TABLE, id.to_string()));
+        }
+
+        // the user must be able to edit each of them
+        for grocery in locked.iter() {
+            Self::get_authorized(&mut tx, utx, grocery.id, true).await?;
+        }
+
+        // the requested order, with the current positions re-assigned in ascending order
+        let mut ordered: Vec<&Grocery> = Vec::new();
+        for id in ids.iter() {
+            if let Some(grocery) = locked.iter().find(|g| g.id == *id) {
+                if !ordered.iter().any(|g| g.id == *id) {
+                    ordered.push(grocery);
+                }
+            }
+        }
+        let mut positions: Vec<i64> = ordered.iter().map(|g| g.position).collect();
+        positions.sort_unstable();
+
+        let mut groceries = Vec::with_capacity(ordered.len());
+        for (grocery, position) in ordered.into_iter().zip(positions) {
+            let sb = sqlb::update()
+                .table(Self::TABLE)
+                .data(vec![("position", position).into()])
+                .and_where_eq("id", grocery.id)
+                .returning(Self::COLUMNS);
+            let grocery: Grocery = sb.fetch_one(&mut tx).await?;
+            groceries.push(grocery);
+        }
+
+        tx.commit().await?;
+
+        Ok(groceries)
+    }
+
+    /// delete - delete a grocery
+    pub async fn delete(db: &Db, utx: &UserCtx, id: i64) -> Result<Grocery, model::Error> {
+        let mut tx = db.begin().await?;
+        Self::get_authorized(&mut tx, utx, id, true).await?;
+        let grocery = Self::delete_checked(&mut tx, id).await?;
+        tx.commit().await?;
+
+        Ok(grocery)
+    }
+
+    /// batch - apply the operations in order, in one transaction (all or nothing),
+    ///   fails with the index of the first failed operation
+    pub async fn batch(
+        db: &Db,
+        utx: &UserCtx,
+        ops: Vec<GroceryOp>,
+    ) -> Result<Vec<GroceryOpResult>, model::Error> {
+        if ops.len() > BATCH_OPS_MAX {
+            return Err(model::Error::Validation(vec![FieldError::new(
+                "ops",
+                format!("cannot have more than {} operations", BATCH_OPS_MAX),
+            )]));
+        }
+
+        let mut tx = db.begin().await?;
+        let mut results = Vec::with_capacity(ops.len());
+        for (index, op) in ops.into_iter().enumerate() {
+            let kind = op.kind();
+            let grocery = Self::apply_op(&mut tx, utx, op)
+                .await
+                .map_err(|ex| model::Error::BatchOp(index, Box::new(ex)))?;
+            results.push(GroceryOpResult {
+                index,
+                op: kind,
+                grocery,
+            });
+        }
+        tx.commit().await?;
+
+        Ok(results)
+    }
+}
+
+/// Writes - on a connection (a transaction of one or many operations),
+///   the permissions being checked beforehand (on the same connection)
+impl GroceryMac {
+    /// apply_op - check and apply one operation of a batch (sees the previous operations)
+    async fn apply_op(
+        con: &mut PgConnection,
+        utx: &UserCtx,
+        op: GroceryOp,
+    ) -> Result<Grocery, model::Error> {
+        match op {
+            GroceryOp::Create { data } => {
+                let data = Self::authorize_create(&mut *con, utx, data).await?;
+                Self::insert(con, utx, data).await
+            }
+            GroceryOp::Update { id, data } => {
+                let (grocery, data) = Self::authorize_update(&mut *con, utx, id, data).await?;
+                Self::update_checked(con, &grocery, data).await
+            }
+            GroceryOp::Delete { id } => {
+                Self::get_authorized(&mut *con, utx, id, true).await?;
+                Self::delete_checked(con, id).await
+            }
+        }
+    }
+
+    /// insert - insert a grocery of the user (fails if it exceeds a strict budget)
+    async fn insert(
+        con: &mut PgConnection,
+        utx: &UserCtx,
+        data: GroceryPatch,
+    ) -> Result<Grocery, model::Error> {
+        let scope = BudgetScope::of(data.list_id, utx.user_id);
+        let total_before = BudgetMac::total(con, &scope).await?;
+
+        let mut fields = data.fields();
+        fields.push(("cid", utx.user_id).into());
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(fields)
+            .returning(Self::COLUMNS);
+
+        let grocery = sb.fetch_one(&mut *con).await?;
+
+        BudgetMac::check_strict(con, &scope, total_before).await?;
+
+        Ok(grocery)
+    }
+
+    /// update_checked - update a grocery (fails if it exceeds a strict budget)
+    async fn update_checked(
+        con: &mut PgConnection,
+        grocery: &Grocery,
+        data: GroceryPatch,
+    ) -> Result<Grocery, model::Error> {
+        let scope = BudgetScope::of(data.list_id.or(grocery.list_id), grocery.cid);
+        let total_before = BudgetMac::total(con, &scope).await?;
+
         let sb = sqlb::update()
             .table(Self::TABLE)
             .data(data.fields())
-            .and_where_eq("id", id)
+            .and_where_eq("id", grocery.id)
             .returning(Self::COLUMNS);
 
-        let result = sb.fetch_one(db).await;
+        let result = sb.fetch_one(&mut *con).await;
+        let grocery = handle_fetch_one_result(result, Self::TABLE, grocery.id)?;
 
-        handle_fetch_one_result(result, Self::TABLE, id)
+        BudgetMac::check_strict(con, &scope, total_before).await?;
+
+        Ok(grocery)
     }
 
-    /// delete - delete a grocery
-    pub async fn delete(db: &Db, _utx: &UserCtx, id: i64) -> Result<Grocery, model::Error> {
+    /// delete_checked - delete a grocery
+    async fn delete_checked(con: &mut PgConnection, id: i64) -> Result<Grocery, model::Error> {
         let sb = sqlb::delete()
             .table(Self::TABLE)
             .returning(Self::COLUMNS)
             .and_where_eq("id", id);
 
-        let result = sb.fetch_one(db).await;
+        let result = sb.fetch_one(con).await;
 
         handle_fetch_one_result(result, Self::TABLE, id)
     }
 }
+
+/// Permission checks
+impl GroceryMac {
+    /// authorize_create - validate a new grocery, the user must be able to edit its list
+    ///   and own its category
+    async fn authorize_create(
+        con: &mut PgConnection,
+        utx: &UserCtx,
+        data: GroceryPatch,
+    ) -> Result<GroceryPatch, model::Error> {
+        let data = data.validate(true)?;
+        // the user must be able to edit the target shopping list
+        if let Some(list_id) = data.list_id {
+            ListMemberMac::require_edit(&mut *con, utx, list_id).await?;
+        }
+        // the category must be one of the user
+        if let Some(category_id) = data.category_id {
+            CategoryMac::get(con, utx, category_id).await?;
+        }
+
+        Ok(data)
+    }
+
+    /// authorize_update - validate a grocery patch, the user must be able to edit the grocery
+    ///   and its target list, and own its category (returns the grocery before the update)
+    async fn authorize_update(
+        con: &mut PgConnection,
+        utx: &UserCtx,
+        id: i64,
+        data: GroceryPatch,
+    ) -> Result<(Grocery, GroceryPatch), model::Error> {
+        let data = data.validate(false)?;
+        let grocery = Self::get_authorized(&mut *con, utx, id, true).await?;
+        // the user must be able to edit the target shopping list
+        if let Some(list_id) = data.list_id {
+            ListMemberMac::require_edit(&mut *con, utx, list_id).await?;
+        }
+        // the category must be one of the user
+        if let Some(category_id) = data.category_id {
+            CategoryMac::get(con, utx, category_id).await?;
+        }
+
+        Ok((grocery, data))
+    }
+
+    /// get_authorized - get a grocery the user can see (and edit if `edit`)
+    ///   - list grocery: by the user role on the shopping list
+    ///   - grocery without list: creator only
+    ///   - on `edit`: the grocery and the user membership stay locked until the end of the
+    ///     transaction (no concurrent delete or role change between the check and the write)
+    async fn get_authorized(
+        con: &mut PgConnection,
+        utx: &UserCtx,
+        id: i64,
+        edit: bool,
+    ) -> Result<Grocery, model::Error> {
+        // Note: sqlb does not support locking clauses, so raw sql here
+        let sql = format!(
+            "SELECT {} FROM {} WHERE id = $1{}",
+            Self::COLUMNS.join(", "),
+            Self::TABLE,
+            if edit { " FOR UPDATE" } else { "" }
+        );
+        let result = sqlx::query_as::<_, Grocery>(&sql)
+            .bind(id)
+            .fetch_one(&mut *con)
+            .await;
+        let grocery = handle_fetch_one_result(result, Self::TABLE, id)?;
+
+        let role = match grocery.list_id {
+            Some(list_id) if edit => ListMemberMac::lock_role(con, utx, list_id).await,
+            Some(list_id) => ListMemberMac::get_role(con, utx, list_id).await,
+            None if grocery.cid == utx.user_id => return Ok(grocery),
+            None => return Err(model::Error::EntityNotFound(Self::TABLE, id.to_string())),
+        };
+        match role {
+            Ok(role) if edit && !role.can_edit() => {
+                Err(model::Error::UserNotAuthorized(Self::TABLE, id.to_string()))
+            }
+            Ok(_) => Ok(grocery),
+            Err(model::Error::EntityNotFound(_, _)) => {
+                Err(model::Error::EntityNotFound(Self::TABLE, id.to_string()))
+            }
+            Err(ex) => Err(ex),
+        }
+    }
+}
 // endregion: GroceryMac
 
 // region:    Utils
+/// sql_where_visible - groceries the user ($1) can see (own groceries and shopping lists groceries)
+/// Note: sqlb does not support OR / sub-selects, so raw sql for these
+fn sql_where_visible() -> String {
+    format!(
+        "((list_id IS NULL AND cid = $1) \
+         OR list_id IN (SELECT list_id FROM {} WHERE user_id = $1 AND NOT pending))",
+        ListMemberMac::TABLE
+    )
+}
+
+/// List query argument (bound in order, as `$1`, `$2`, ...)
+enum ListArg {
+    Int(i64),
+    Text(String),
+    Status(GroceryStatus),
+}
+
+/// push_arg - add a list query argument, returns its placeholder
+fn push_arg(args: &mut Vec<ListArg>, arg: ListArg) -> String {
+    args.push(arg);
+    format!("${}", args.len())
+}
+
+/// sort_column - column of a sort (`created` is the id order)
+fn sort_column(sort: GrocerySort) -> &'static str {
+    match sort {
+        GrocerySort::Name => "name",
+        GrocerySort::Cost => "cost",
+        GrocerySort::Position => "position",
+        GrocerySort::Created => "id",
+    }
+}
+
+/// like_contains - ILIKE pattern of a substring (escapes the wildcards)
+fn like_contains(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// decode_cursor - decode a `next` cursor (none if invalid)
+fn decode_cursor(cursor: &str) -> Option<GroceryCursor> {
+    let cursor = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    serde_json::from_slice(&cursor).ok()
+}
+
 /// handle_fetch_one_result - handle sqlx::Error
 fn handle_fetch_one_result(
     result: Result<Grocery, sqlx::Error>,
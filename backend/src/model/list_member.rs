@@ -0,0 +1,275 @@
+use super::db::Db;
+use crate::{
+    model::{self, FieldError},
+    security::UserCtx,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, PgConnection, Postgres};
+
+// region:    ListMember Types
+/// List Member - a user's role on a shopping list
+#[derive(sqlx::FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct ListMember {
+    pub list_id: i64,
+    pub user_id: i64,
+    pub role: ListRole,
+    pub pending: bool, // invitation not yet accepted
+}
+
+/// List Role
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[sqlx(type_name = "list_role_enum")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ListRole {
+    Owner,
+    Editor,
+    Viewer,
+}
+sqlb::bindable!(ListRole);
+
+impl ListRole {
+    /// can_edit - owners and editors can change the list groceries
+    pub fn can_edit(&self) -> bool {
+        matches!(self, ListRole::Owner | ListRole::Editor)
+    }
+}
+// endregion: ListMember Types
+
+// region:    ListMemberMac
+/// List Member Model Access Controller
+pub struct ListMemberMac;
+
+impl ListMemberMac {
+    pub(super) const TABLE: &'static str = "list_members";
+    const COLUMNS: &'static [&'static str] = &["list_id", "user_id", "role", "pending"];
+    const LIST_TABLE: &'static str = "shopping_lists";
+}
+
+impl ListMemberMac {
+    /// invite - invite a user to a shopping list (owner only), as editor or viewer
+    ///   (a list has a single owner, who cannot be removed)
+    pub async fn invite(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: i64,
+        user_id: i64,
+        role: ListRole,
+    ) -> Result<ListMember, model::Error> {
+        Self::require_owner(db, utx, list_id).await?;
+        if role == ListRole::Owner {
+            return Err(model::Error::Validation(vec![FieldError::new(
+                "role",
+                "cannot be owner",
+            )]));
+        }
+
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(vec![
+                ("list_id", list_id).into(),
+                ("user_id", user_id).into(),
+                ("role", role).into(),
+                ("pending", true).into(),
+            ])
+            .returning(Self::COLUMNS);
+
+        let member = sb.fetch_one(db).await?;
+
+        Ok(member)
+    }
+
+    /// accept - accept an invitation to a shopping list
+    pub async fn accept(db: &Db, utx: &UserCtx, list_id: i64) -> Result<ListMember, model::Error> {
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(vec![("pending", false).into()])
+            .and_where_eq("list_id", list_id)
+            .and_where_eq("user_id", utx.user_id)
+            .and_where_eq("pending", true)
+            .returning(Self::COLUMNS);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, list_id)
+    }
+
+    /// list - get the members of a shopping list (any member)
+    pub async fn list(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<Vec<ListMember>, model::Error> {
+        Self::get_role(db, utx, list_id).await?;
+
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("list_id", list_id)
+            .order_by("user_id");
+
+        // execute the query
+        let members = sb.fetch_all(db).await?;
+
+        Ok(members)
+    }
+
+    /// list_invitations - get the pending invitations of the user
+    pub async fn list_invitations(db: &Db, utx: &UserCtx) -> Result<Vec<ListMember>, model::Error> {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("user_id", utx.user_id)
+            .and_where_eq("pending", true)
+            .order_by("list_id");
+
+        // execute the query
+        let members = sb.fetch_all(db).await?;
+
+        Ok(members)
+    }
+
+    /// remove - remove a member (owner), or leave / decline (the member itself)
+    pub async fn remove(
+        db: &Db,
+        utx: &UserCtx,
+        list_id: i64,
+        user_id: i64,
+    ) -> Result<ListMember, model::Error> {
+        if user_id != utx.user_id {
+            Self::require_owner(db, utx, list_id).await?;
+        }
+
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("list_id", list_id)
+            .and_where_eq("user_id", user_id);
+        let member: ListMember =
+            handle_fetch_one_result(sb.fetch_one(db).await, Self::TABLE, list_id)?;
+        // the owner cannot be removed from its list
+        if member.role == ListRole::Owner {
+            return Err(model::Error::UserNotAuthorized(
+                Self::LIST_TABLE,
+                list_id.to_string(),
+            ));
+        }
+
+        let sb = sqlb::delete()
+            .table(Self::TABLE)
+            .returning(Self::COLUMNS)
+            .and_where_eq("list_id", list_id)
+            .and_where_eq("user_id", user_id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, list_id)
+    }
+}
+
+/// Permission checks (a non member gets `EntityNotFound` on the shopping list)
+impl ListMemberMac {
+    /// get_role - get the role of the user on a shopping list
+    ///   (on the pool, or on the connection of a transaction)
+    pub(super) async fn get_role<'e, E>(
+        db: E,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<ListRole, model::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("list_id", list_id)
+            .and_where_eq("user_id", utx.user_id)
+            .and_where_eq("pending", false);
+
+        let result = sb.fetch_one(db).await;
+        let member = handle_fetch_one_result(result, Self::LIST_TABLE, list_id)?;
+
+        Ok(member.role)
+    }
+
+    /// lock_role - get the user role on a shopping list, and lock the membership (`FOR SHARE`)
+    ///   so a role change or removal waits for the end of the transaction
+    pub(super) async fn lock_role(
+        con: &mut PgConnection,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<ListRole, model::Error> {
+        // Note: sqlb does not support locking clauses, so raw sql here
+        let sql = format!(
+            "SELECT {} FROM {} WHERE list_id = $1 AND user_id = $2 AND NOT pending FOR SHARE",
+            Self::COLUMNS.join(", "),
+            Self::TABLE
+        );
+        let result = sqlx::query_as::<_, ListMember>(&sql)
+            .bind(list_id)
+            .bind(utx.user_id)
+            .fetch_one(con)
+            .await;
+        let member = handle_fetch_one_result(result, Self::LIST_TABLE, list_id)?;
+
+        Ok(member.role)
+    }
+
+    /// require_edit - the user must be an owner or editor of the shopping list
+    pub async fn require_edit<'e, E>(
+        db: E,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<ListRole, model::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let role = Self::get_role(db, utx, list_id).await?;
+        if !role.can_edit() {
+            return Err(model::Error::UserNotAuthorized(
+                Self::LIST_TABLE,
+                list_id.to_string(),
+            ));
+        }
+
+        Ok(role)
+    }
+
+    /// require_owner - the user must be the owner of the shopping list
+    pub async fn require_owner<'e, E>(
+        db: E,
+        utx: &UserCtx,
+        list_id: i64,
+    ) -> Result<ListRole, model::Error>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let role = Self::get_role(db, utx, list_id).await?;
+        if role != ListRole::Owner {
+            return Err(model::Error::UserNotAuthorized(
+                Self::LIST_TABLE,
+                list_id.to_string(),
+            ));
+        }
+
+        Ok(role)
+    }
+}
+// endregion: ListMemberMac
+
+// region:    Utils
+/// handle_fetch_one_result - handle sqlx::Error
+fn handle_fetch_one_result(
+    result: Result<ListMember, sqlx::Error>,
+    typ: &'static str,
+    list_id: i64,
+) -> Result<ListMember, model::Error> {
+    result.map_err(|sqlx_error| match sqlx_error {
+        sqlx::Error::RowNotFound => model::Error::EntityNotFound(typ, list_id.to_string()),
+        other => model::Error::Sqlx(other),
+    })
+}
+// endregion: Utils
+
+#[cfg(test)]
+#[path = "../_tests/model_list_member.rs"]
+mod tests;
@@ -0,0 +1,388 @@
+use super::db::Db;
+use super::sql_script::exec_script;
+use crate::model;
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, PgConnection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TABLE: &str = "schema_migrations";
+// advisory lock key of the migration runners (one at a time, e.g. replicas starting together),
+// taken per transaction so it is released on commit, rollback, or a lost connection
+const LOCK_KEY: i64 = 0x5348_4f50_4c49_5354;
+
+// region:    Migration Types
+/// Migration - a versioned sql file (e.g. `0001-create-schema.sql`),
+/// with its optional revert file (e.g. `0001-create-schema.down.sql`)
+struct Migration {
+    file: String,
+    version: i64,
+    name: String,
+    sql: String,
+    checksum: String,
+    down_file: Option<PathBuf>,
+}
+
+/// Applied migration (row of `schema_migrations`)
+#[derive(sqlx::FromRow)]
+struct AppliedMigration {
+    version: i64,
+    name: String,
+    checksum: String,
+    applied_at: String,
+}
+
+/// Expected Migration - version, name and checksum of a migration file
+///   (loaded once, to check the applied migrations without reading the files again)
+#[derive(Debug, Clone)]
+pub struct ExpectedMigration {
+    pub version: i64,
+    pub name: String,
+    checksum: String,
+}
+
+/// Migration Status
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<String>,
+}
+
+/// Migration State
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationState {
+    Pending,
+    Applied,
+    Edited,  // applied, but the file changed since
+    Missing, // applied, but the file is not found
+}
+// endregion: Migration Types
+
+/// migrate - apply the pending migrations of a directory, in version order, once each
+///   (each migration in its own transaction, under the lock)
+pub(super) async fn migrate(db: &Db, dir: &str) -> Result<(), model::Error> {
+    let migrations = load_migrations(dir)?;
+
+    let mut con = db.acquire().await?;
+    for migration in migrations {
+        let mut tx = con.begin().await?;
+        lock(&mut tx).await?;
+        create_table(&mut tx).await?;
+
+        // the applied ones read under the lock (e.g. applied meanwhile by another runner)
+        let applied = fetch_applied(&mut tx).await?;
+        match applied.iter().find(|a| a.version == migration.version) {
+            // already applied, the file must not have been edited since
+            Some(applied) => {
+                if applied.checksum != migration.checksum {
+                    return Err(model::Error::MigrationChecksum(
+                        migration.version,
+                        applied.name.to_string(),
+                    ));
+                }
+            }
+            None => apply(&mut tx, &migration).await?,
+        }
+
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// apply - execute a migration and record it (in the transaction of the caller)
+async fn apply(con: &mut PgConnection, migration: &Migration) -> Result<(), model::Error> {
+    exec_script(&mut *con, &migration.file, &migration.sql).await?;
+
+    sqlx::query(&format!(
+        "INSERT INTO {} (version, name, checksum) VALUES ($1, $2, $3)",
+        TABLE
+    ))
+    .bind(migration.version)
+    .bind(&migration.name)
+    .bind(&migration.checksum)
+    .execute(con)
+    .await?;
+
+    tracing::info!(
+        version = migration.version,
+        name = %migration.name,
+        "migration applied"
+    );
+
+    Ok(())
+}
+
+/// status - get the status of the migrations of a directory and of the applied migrations
+pub(super) async fn status(db: &Db, dir: &str) -> Result<Vec<MigrationStatus>, model::Error> {
+    let mut con = db.acquire().await?;
+    let applied = fetch_applied(&mut con).await?;
+    let migrations = load_migrations(dir)?;
+
+    let mut statuses: Vec<MigrationStatus> = migrations
+        .iter()
+        .map(|migration| {
+            let applied = applied.iter().find(|a| a.version == migration.version);
+            let state = match applied {
+                None => MigrationState::Pending,
+                Some(applied) if applied.checksum != migration.checksum => MigrationState::Edited,
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus {
+                version: migration.version,
+                name: migration.name.to_string(),
+                state,
+                applied_at: applied.map(|a| a.applied_at.to_string()),
+            }
+        })
+        .collect();
+
+    // applied migrations without file
+    for applied in applied {
+        if !migrations.iter().any(|m| m.version == applied.version) {
+            statuses.push(MigrationStatus {
+                version: applied.version,
+                name: applied.name,
+                state: MigrationState::Missing,
+                applied_at: Some(applied.applied_at),
+            });
+        }
+    }
+    statuses.sort_by_key(|s| s.version);
+
+    Ok(statuses)
+}
+
+/// load_expected - get the expected migrations of a directory (version order)
+pub(super) fn load_expected(dir: &str) -> Result<Vec<ExpectedMigration>, model::Error> {
+    let expected = load_migrations(dir)?
+        .into_iter()
+        .map(|migration| ExpectedMigration {
+            version: migration.version,
+            name: migration.name,
+            checksum: migration.checksum,
+        })
+        .collect();
+
+    Ok(expected)
+}
+
+/// check_applied - check every expected migration is applied, and no other one (read only),
+/// returns the last applied version
+pub(super) async fn check_applied(
+    db: &Db,
+    expected: &[ExpectedMigration],
+) -> Result<Option<i64>, model::Error> {
+    let applied = sqlx::query_as::<_, (i64, String, String)>(&format!(
+        "SELECT version, name, checksum FROM {} ORDER BY version",
+        TABLE
+    ))
+    .fetch_all(db)
+    .await?;
+
+    for migration in expected {
+        let state = match applied
+            .iter()
+            .find(|(version, ..)| *version == migration.version)
+        {
+            None => MigrationState::Pending,
+            Some((_, _, checksum)) if *checksum != migration.checksum => MigrationState::Edited,
+            Some(_) => continue,
+        };
+        return Err(model::Error::MigrationNotApplied(
+            migration.version,
+            migration.name.to_string(),
+            state,
+        ));
+    }
+    // applied migrations without file
+    if let Some((version, name, _)) = applied
+        .iter()
+        .find(|(version, ..)| !expected.iter().any(|m| m.version == *version))
+    {
+        return Err(model::Error::MigrationNotApplied(
+            *version,
+            name.to_string(),
+            MigrationState::Missing,
+        ));
+    }
+
+    Ok(applied.last().map(|(version, ..)| *version))
+}
+
+/// revert_last - revert the last applied migration with its `.down.sql` file (None if none applied)
+pub(super) async fn revert_last(
+    db: &Db,
+    dir: &str,
+) -> Result<Option<MigrationStatus>, model::Error> {
+    let migrations = load_migrations(dir)?;
+
+    let mut tx = db.begin().await?;
+    lock(&mut tx).await?;
+
+    // the last applied migration, read under the lock
+    let applied = fetch_applied(&mut tx).await?;
+    let last = match applied.last() {
+        Some(last) => last,
+        None => return Ok(None),
+    };
+
+    let migration = migrations
+        .iter()
+        .find(|m| m.version == last.version)
+        .ok_or_else(|| {
+            model::Error::MigrationInvalid(
+                format!("{:04}-{}", last.version, last.name),
+                "migration file not found",
+            )
+        })?;
+    let down_file = migration.down_file.as_ref().ok_or_else(|| {
+        model::Error::MigrationInvalid(migration.file.to_string(), "no down migration")
+    })?;
+    let down_file = down_file.display().to_string();
+    let sql = fs::read_to_string(&down_file)?;
+
+    exec_script(&mut tx, &down_file, &sql).await?;
+    sqlx::query(&format!("DELETE FROM {} WHERE version = $1", TABLE))
+        .bind(migration.version)
+        .execute(&mut tx)
+        .await?;
+    tx.commit().await?;
+    tracing::info!(
+        version = migration.version,
+        name = %migration.name,
+        "migration reverted"
+    );
+
+    Ok(Some(MigrationStatus {
+        version: migration.version,
+        name: migration.name.to_string(),
+        state: MigrationState::Pending,
+        applied_at: None,
+    }))
+}
+
+/// lock - wait for the migration advisory lock (transaction level, released when it ends)
+async fn lock(con: &mut PgConnection) -> Result<(), model::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(LOCK_KEY)
+        .execute(con)
+        .await?;
+
+    Ok(())
+}
+
+/// create_table - create the tracking table if needed (under the lock)
+async fn create_table(con: &mut PgConnection) -> Result<(), model::Error> {
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {} ( \
+            version bigint PRIMARY KEY, \
+            name text NOT NULL, \
+            checksum text NOT NULL, \
+            applied_at timestamptz NOT NULL DEFAULT now() \
+        )",
+        TABLE
+    ))
+    .execute(con)
+    .await?;
+
+    Ok(())
+}
+
+/// fetch_applied - get the applied migrations (none if no tracking table yet, read only)
+async fn fetch_applied(con: &mut PgConnection) -> Result<Vec<AppliedMigration>, model::Error> {
+    let (exists,) = sqlx::query_as::<_, (bool,)>("SELECT to_regclass($1) IS NOT NULL")
+        .bind(TABLE)
+        .fetch_one(&mut *con)
+        .await?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+
+    let applied = sqlx::query_as::<_, AppliedMigration>(&format!(
+        "SELECT version, name, checksum, applied_at::text AS applied_at FROM {} ORDER BY version",
+        TABLE
+    ))
+    .fetch_all(con)
+    .await?;
+
+    Ok(applied)
+}
+
+/// load_migrations - read the `.sql` files of a directory, sorted by version
+fn load_migrations(dir: &str) -> Result<Vec<Migration>, model::Error> {
+    let mut migrations: Vec<Migration> = Vec::new();
+    let mut down_files: Vec<(i64, PathBuf)> = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("sql") {
+            continue;
+        }
+        let (version, name) = parse_file_name(&path)?;
+        if name.ends_with(".down") {
+            down_files.push((version, path));
+            continue;
+        }
+        if migrations.iter().any(|m| m.version == version) {
+            return Err(model::Error::MigrationInvalid(
+                path.display().to_string(),
+                "duplicate version",
+            ));
+        }
+        let sql = fs::read_to_string(&path)?;
+        let checksum = checksum(&sql);
+        migrations.push(Migration {
+            file: path.display().to_string(),
+            version,
+            name,
+            sql,
+            checksum,
+            down_file: None,
+        });
+    }
+    for (version, path) in down_files {
+        match migrations.iter_mut().find(|m| m.version == version) {
+            Some(migration) => migration.down_file = Some(path),
+            None => {
+                return Err(model::Error::MigrationInvalid(
+                    path.display().to_string(),
+                    "down migration without migration",
+                ))
+            }
+        }
+    }
+    migrations.sort_by_key(|m| m.version);
+
+    Ok(migrations)
+}
+
+// region:    Utils
+/// Parse `0001-create-schema.sql` into (1, "create-schema")
+fn parse_file_name(path: &Path) -> Result<(i64, String), model::Error> {
+    let invalid =
+        || model::Error::MigrationInvalid(path.display().to_string(), "expected 'NNNN-name.sql'");
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(invalid)?;
+    let (version, name) = stem.split_once('-').ok_or_else(invalid)?;
+    let version = version.parse::<i64>().map_err(|_| invalid())?;
+
+    Ok((version, name.to_string()))
+}
+
+/// Hex sha256 of the migration content
+fn checksum(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+// endregion: Utils
+
+#[cfg(test)]
+#[path = "../_tests/model_migration.rs"]
+mod tests;
@@ -1,19 +1,96 @@
+mod budget;
+mod category;
 mod db;
 mod grocery;
+mod list_member;
+mod migration;
+mod session;
+mod shopping_list;
+mod sql_script;
+mod user;
+
+use serde::Serialize;
 
 // re-export
-pub use db::init_db;
+pub use budget::{Budget, BudgetLevel, BudgetMac, BudgetScope, BudgetStatus, GroceryWithBudget};
+pub use category::{Category, CategoryMac, CategoryPatch};
 pub use db::Db;
-pub use grocery::{Grocery, GroceryMac, GroceryPatch, GroceryStatus};
+pub use db::{
+    check_ready, connect_db, expected_migrations, init_db, migrate_down, migrate_status,
+    migrate_up, seed_db,
+};
+pub use grocery::{
+    Grocery, GroceryGroup, GroceryListOptions, GroceryMac, GroceryOp, GroceryOpKind,
+    GroceryOpResult, GroceryPatch, GrocerySort, GroceryStatus, GrocerySummary, Quantity,
+};
+pub use list_member::{ListMember, ListMemberMac, ListRole};
+pub use migration::{ExpectedMigration, MigrationState, MigrationStatus};
+pub use session::{Session, SessionMac};
+pub use shopping_list::{ShoppingList, ShoppingListMac, ShoppingListPatch};
+pub use user::{User, UserForAuth, UserMac, UserPatch};
 
 // region:    Error
+/// Field Error - a validation error of an input field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        FieldError {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
 /// model error
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Entity Not Found - {0}[{1}] ")]
     EntityNotFound(&'static str, String),
 
+    #[error("User Not Authorized - {0}[{1}] ")]
+    UserNotAuthorized(&'static str, String),
+
+    #[error("Budget Exceeded - limit {0}, total {1} ")]
+    BudgetExceeded(i64, i64),
+
+    #[error("Validation Failed - {} ", fmt_field_errors(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("Batch Operation Failed - operation {0}: {1}")]
+    BatchOp(usize, Box<Error>),
+
+    #[error("Migration Checksum Mismatch - {0:04} '{1}' was edited after being applied ")]
+    MigrationChecksum(i64, String),
+
+    #[error("Migration Invalid - {0} ({1}) ")]
+    MigrationInvalid(String, &'static str),
+
+    #[error("Migration Not Applied - {0:04} '{1}' is {2:?} ")]
+    MigrationNotApplied(i64, String, MigrationState),
+
+    #[error("Sql Script Failed - {0} line {1}: {2} ")]
+    SqlScript(String, usize, String),
+
     #[error(transparent)]
     Sqlx(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
 }
 // endregion: Error
+
+// region:    Utils
+/// Format field errors as `name: message, cost: message`
+fn fmt_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("{}: {}", error.field, error.message))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+// endregion: Utils
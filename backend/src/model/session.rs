@@ -0,0 +1,118 @@
+use super::db::Db;
+use crate::model;
+use serde::{Deserialize, Serialize};
+
+// region:    Session Types
+/// Session
+#[derive(sqlx::FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: i64,
+    pub user_id: i64,
+    pub exp: i64, // expiration (unix seconds)
+    pub revoked: bool,
+}
+// endregion: Session Types
+
+// region:    SessionMac
+/// Session Model Access Controller
+pub struct SessionMac;
+
+impl SessionMac {
+    const TABLE: &'static str = "sessions";
+    const COLUMNS: &'static [&'static str] = &["id", "user_id", "exp", "revoked"];
+}
+
+impl SessionMac {
+    /// create - create a new session for a user
+    pub async fn create(db: &Db, user_id: i64, exp: i64) -> Result<Session, model::Error> {
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(vec![("user_id", user_id).into(), ("exp", exp).into()])
+            .returning(Self::COLUMNS);
+
+        let session = sb.fetch_one(db).await?;
+
+        Ok(session)
+    }
+
+    /// get - get a session
+    pub async fn get(db: &Db, id: i64) -> Result<Session, model::Error> {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("id", id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+
+    /// revoke - revoke a session
+    pub async fn revoke(db: &Db, id: i64) -> Result<Session, model::Error> {
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(vec![("revoked", true).into()])
+            .and_where_eq("id", id)
+            .returning(Self::COLUMNS);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+
+    /// rotate - revoke a session (if not yet revoked) and create a new session of its user,
+    ///   in one transaction (of concurrent rotations, only one succeeds)
+    pub async fn rotate(db: &Db, id: i64, exp: i64) -> Result<Session, model::Error> {
+        let mut tx = db.begin().await?;
+
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(vec![("revoked", true).into()])
+            .and_where_eq("id", id)
+            .and_where_eq("revoked", false)
+            .returning(Self::COLUMNS);
+        let result = sb.fetch_one(&mut tx).await;
+        let revoked = handle_fetch_one_result(result, Self::TABLE, id)?;
+
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(vec![
+                ("user_id", revoked.user_id).into(),
+                ("exp", exp).into(),
+            ])
+            .returning(Self::COLUMNS);
+        let session = sb.fetch_one(&mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    /// revoke_all - revoke all the sessions of a user, returns the number of revoked sessions
+    pub async fn revoke_all(db: &Db, user_id: i64) -> Result<u64, model::Error> {
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(vec![("revoked", true).into()])
+            .and_where_eq("user_id", user_id)
+            .and_where_eq("revoked", false);
+
+        let count = sb.exec(db).await?;
+
+        Ok(count)
+    }
+}
+// endregion: SessionMac
+
+// region:    Utils
+/// handle_fetch_one_result - handle sqlx::Error
+fn handle_fetch_one_result(
+    result: Result<Session, sqlx::Error>,
+    typ: &'static str,
+    id: i64,
+) -> Result<Session, model::Error> {
+    result.map_err(|sqlx_error| match sqlx_error {
+        sqlx::Error::RowNotFound => model::Error::EntityNotFound(typ, id.to_string()),
+        other => model::Error::Sqlx(other),
+    })
+}
+// endregion: Utils
@@ -0,0 +1,192 @@
+use super::db::Db;
+use super::list_member::{ListMemberMac, ListRole};
+use crate::{
+    model::{self, FieldError},
+    security::UserCtx,
+};
+use serde::{Deserialize, Serialize};
+use sqlb::HasFields;
+
+// region:    ShoppingList Types
+/// Shopping List
+#[derive(sqlx::FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingList {
+    pub id: i64,
+    pub cid: i64, // creator id
+    pub name: String,
+}
+
+/// Shopping List Patch
+#[derive(sqlb::Fields, Default, Debug, Clone, Deserialize)]
+pub struct ShoppingListPatch {
+    pub name: Option<String>,
+}
+// endregion: ShoppingList Types
+
+// region:    ShoppingListPatch Validation
+const NAME_MAX_LEN: usize = 100;
+
+impl ShoppingListPatch {
+    /// validate - trim the name and check it (name is required on create,
+    /// and one field at least on update), fails with the list of the field errors
+    pub fn validate(mut self, create: bool) -> Result<ShoppingListPatch, model::Error> {
+        let mut errors = Vec::new();
+
+        self.name = self.name.map(|name| name.trim().to_string());
+        match &self.name {
+            None if create => errors.push(FieldError::new("name", "is required")),
+            Some(name) if name.is_empty() => {
+                errors.push(FieldError::new("name", "cannot be empty"))
+            }
+            Some(name) if name.chars().count() > NAME_MAX_LEN => errors.push(FieldError::new(
+                "name",
+                format!("cannot be longer than {} characters", NAME_MAX_LEN),
+            )),
+            _ => (),
+        }
+
+        // an update must set at least one field
+        if !create && self.fields().is_empty() {
+            errors.push(FieldError::new("patch", "has no field to update"));
+        }
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(model::Error::Validation(errors))
+        }
+    }
+}
+// endregion: ShoppingListPatch Validation
+
+// region:    ShoppingListMac
+/// Shopping List Model Access Controller
+pub struct ShoppingListMac;
+
+impl ShoppingListMac {
+    const TABLE: &'static str = "shopping_lists";
+    const COLUMNS: &'static [&'static str] = &["id", "cid", "name"];
+}
+
+impl ShoppingListMac {
+    /// create - create a new shopping list (the user becomes its owner)
+    pub async fn create(
+        db: &Db,
+        utx: &UserCtx,
+        data: ShoppingListPatch,
+    ) -> Result<ShoppingList, model::Error> {
+        let data = data.validate(true)?;
+
+        let mut tx = db.begin().await?;
+
+        let mut fields = data.fields();
+        fields.push(("cid", utx.user_id).into());
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(fields)
+            .returning(Self::COLUMNS);
+        let shopping_list: ShoppingList = sb.fetch_one(&mut tx).await?;
+
+        let sb = sqlb::insert().table(ListMemberMac::TABLE).data(vec![
+            ("list_id", shopping_list.id).into(),
+            ("user_id", utx.user_id).into(),
+            ("role", ListRole::Owner).into(),
+            ("pending", false).into(),
+        ]);
+        sb.exec(&mut tx).await?;
+
+        tx.commit().await?;
+
+        Ok(shopping_list)
+    }
+
+    /// get - get a shopping list (any member)
+    pub async fn get(db: &Db, utx: &UserCtx, id: i64) -> Result<ShoppingList, model::Error> {
+        ListMemberMac::get_role(db, utx, id).await?;
+
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("id", id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+
+    /// list - get list of shopping lists the user is a member of
+    pub async fn list(db: &Db, utx: &UserCtx) -> Result<Vec<ShoppingList>, model::Error> {
+        // Note: sqlb does not support joins, so raw sql here
+        let sql = format!(
+            "SELECT {} FROM {} WHERE id IN \
+             (SELECT list_id FROM {} WHERE user_id = $1 AND NOT pending) \
+             ORDER BY id",
+            Self::COLUMNS.join(", "),
+            Self::TABLE,
+            ListMemberMac::TABLE
+        );
+
+        // execute the query
+        let shopping_lists = sqlx::query_as::<_, ShoppingList>(&sql)
+            .bind(utx.user_id)
+            .fetch_all(db)
+            .await?;
+
+        Ok(shopping_lists)
+    }
+
+    /// update - update a shopping list (owner or editor)
+    pub async fn update(
+        db: &Db,
+        utx: &UserCtx,
+        id: i64,
+        data: ShoppingListPatch,
+    ) -> Result<ShoppingList, model::Error> {
+        let data = data.validate(false)?;
+
+        ListMemberMac::require_edit(db, utx, id).await?;
+
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(data.fields())
+            .and_where_eq("id", id)
+            .returning(Self::COLUMNS);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+
+    /// delete - delete a shopping list and its groceries (owner only)
+    pub async fn delete(db: &Db, utx: &UserCtx, id: i64) -> Result<ShoppingList, model::Error> {
+        ListMemberMac::require_owner(db, utx, id).await?;
+
+        let sb = sqlb::delete()
+            .table(Self::TABLE)
+            .returning(Self::COLUMNS)
+            .and_where_eq("id", id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, id)
+    }
+}
+// endregion: ShoppingListMac
+
+// region:    Utils
+/// handle_fetch_one_result - handle sqlx::Error
+fn handle_fetch_one_result(
+    result: Result<ShoppingList, sqlx::Error>,
+    typ: &'static str,
+    id: i64,
+) -> Result<ShoppingList, model::Error> {
+    result.map_err(|sqlx_error| match sqlx_error {
+        sqlx::Error::RowNotFound => model::Error::EntityNotFound(typ, id.to_string()),
+        other => model::Error::Sqlx(other),
+    })
+}
+// endregion: Utils
+
+#[cfg(test)]
+#[path = "../_tests/model_shopping_list.rs"]
+mod tests;
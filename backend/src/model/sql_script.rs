@@ -0,0 +1,192 @@
+use crate::model;
+use sqlx::PgConnection;
+
+/// Sql Statement - a statement of a sql script, with its first line (1-based)
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct SqlStatement<'a> {
+    pub sql: &'a str,
+    pub line: usize,
+}
+
+/// exec_script - execute the statements of a sql script, failing on the first statement error
+pub(super) async fn exec_script(
+    con: &mut PgConnection,
+    file: &str,
+    script: &str,
+) -> Result<(), model::Error> {
+    for statement in split_sql(script) {
+        sqlx::query(statement.sql)
+            .execute(&mut *con)
+            .await
+            .map_err(|ex| {
+                model::Error::SqlScript(file.to_string(), statement.line, ex.to_string())
+            })?;
+    }
+
+    Ok(())
+}
+
+/// split_sql - split a sql script on the `;` that are not in a string, a quoted identifier,
+/// a dollar-quoted body or a comment. Comment-only statements are skipped.
+pub(super) fn split_sql(script: &str) -> Vec<SqlStatement<'_>> {
+    let bytes = script.as_bytes();
+    let len = bytes.len();
+    let mut statements = Vec::new();
+
+    let mut i = 0;
+    let mut line = 1;
+    let mut start = 0; // start of the current statement
+    let mut start_line: Option<usize> = None; // line of the first code of the current statement
+
+    while i < len {
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        match c {
+            b'\n' => {
+                line += 1;
+                i += 1;
+            }
+            // -- line comment (up to the end of line)
+            b'-' if next == Some(b'-') => {
+                while i < len && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            // -- block comment (can be nested)
+            b'/' if next == Some(b'*') => {
+                let mut depth = 0;
+                while i < len {
+                    match (bytes[i], bytes.get(i + 1).copied()) {
+                        (b'/', Some(b'*')) => {
+                            depth += 1;
+                            i += 2;
+                        }
+                        (b'*', Some(b'/')) => {
+                            depth -= 1;
+                            i += 2;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        (b'\n', _) => {
+                            line += 1;
+                            i += 1;
+                        }
+                        _ => i += 1,
+                    }
+                }
+            }
+            // -- string literal (`''` escape, and backslash escapes for `E'...'`)
+            b'\'' => {
+                start_line.get_or_insert(line);
+                let escape_string = i > 0
+                    && matches!(bytes[i - 1], b'E' | b'e')
+                    && (i < 2 || !is_ident_char(bytes[i - 2]));
+                i = skip_quoted(bytes, i, b'\'', escape_string, &mut line);
+            }
+            // -- quoted identifier (`""` escape)
+            b'"' => {
+                start_line.get_or_insert(line);
+                i = skip_quoted(bytes, i, b'"', false, &mut line);
+            }
+            // -- dollar-quoted body (`$$ ... $$` or `$tag$ ... $tag$`)
+            b'$' if i == 0 || !is_ident_char(bytes[i - 1]) => {
+                start_line.get_or_insert(line);
+                match dollar_tag(bytes, i) {
+                    Some(tag) => {
+                        i += tag.len();
+                        while i < len && !bytes[i..].starts_with(tag) {
+                            if bytes[i] == b'\n' {
+                                line += 1;
+                            }
+                            i += 1;
+                        }
+                        i = (i + tag.len()).min(len);
+                    }
+                    // e.g. `$1` parameter
+                    None => i += 1,
+                }
+            }
+            b';' => {
+                if let Some(line) = start_line.take() {
+                    statements.push(SqlStatement {
+                        sql: script[start..i].trim(),
+                        line,
+                    });
+                }
+                i += 1;
+                start = i;
+            }
+            c => {
+                if !c.is_ascii_whitespace() {
+                    start_line.get_or_insert(line);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    // -- last statement without `;`
+    if let Some(line) = start_line {
+        statements.push(SqlStatement {
+            sql: script[start..].trim(),
+            line,
+        });
+    }
+
+    statements
+}
+
+// region:    Utils
+/// Skip a quoted string or identifier starting at `i`, returns the index after the closing quote
+fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8, backslash: bool, line: &mut usize) -> usize {
+    i += 1; // opening quote
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if backslash => {
+                // the escaped char can be a line break
+                if bytes.get(i + 1) == Some(&b'\n') {
+                    *line += 1;
+                }
+                i += 2;
+            }
+            c if c == quote => {
+                // doubled quote is an escaped quote
+                if bytes.get(i + 1) == Some(&quote) {
+                    i += 2;
+                } else {
+                    return i + 1;
+                }
+            }
+            b'\n' => {
+                *line += 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Dollar quote tag starting at `i` (e.g. `$$` or `$body$`), None if not a tag
+fn dollar_tag(bytes: &[u8], i: usize) -> Option<&[u8]> {
+    let mut end = i + 1;
+    while end < bytes.len() && bytes[end] != b'$' {
+        let c = bytes[end];
+        // tag is an identifier (not starting with a digit)
+        if !is_ident_char(c) || (end == i + 1 && c.is_ascii_digit()) {
+            return None;
+        }
+        end += 1;
+    }
+    (end < bytes.len()).then(|| &bytes[i..=end])
+}
+
+fn is_ident_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80
+}
+// endregion: Utils
+
+#[cfg(test)]
+#[path = "../_tests/model_sql_script.rs"]
+mod tests;
@@ -0,0 +1,120 @@
+use super::db::Db;
+use crate::model;
+use serde::{Deserialize, Serialize};
+use sqlb::HasFields;
+
+// region:    User Types
+/// User
+#[derive(sqlx::FromRow, Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+}
+
+/// User with password hash (login only, never sent to the client)
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct UserForAuth {
+    pub id: i64,
+    pub username: String,
+    pub pwd: String,
+}
+
+/// User Patch (pwd is the password hash)
+#[derive(sqlb::Fields, Default, Debug, Clone)]
+pub struct UserPatch {
+    pub username: Option<String>,
+    pub pwd: Option<String>,
+}
+// endregion: User Types
+
+// region:    UserMac
+/// User Model Access Controller
+pub struct UserMac;
+
+impl UserMac {
+    const TABLE: &'static str = "users";
+    const COLUMNS: &'static [&'static str] = &["id", "username"];
+    const AUTH_COLUMNS: &'static [&'static str] = &["id", "username", "pwd"];
+}
+
+impl UserMac {
+    /// create - create a new user
+    pub async fn create(db: &Db, data: UserPatch) -> Result<User, model::Error> {
+        let sb = sqlb::insert()
+            .table(Self::TABLE)
+            .data(data.fields())
+            .returning(Self::COLUMNS);
+
+        let user = sb.fetch_one(db).await?;
+
+        Ok(user)
+    }
+
+    /// get - get a user
+    pub async fn get(db: &Db, id: i64) -> Result<User, model::Error> {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("id", id);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, &id.to_string())
+    }
+
+    /// get_by_username - get a user by username
+    pub async fn get_by_username(db: &Db, username: &str) -> Result<User, model::Error> {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::COLUMNS)
+            .and_where_eq("username", username);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, username)
+    }
+
+    /// get_for_auth - get a user with its password hash by username
+    pub async fn get_for_auth(db: &Db, username: &str) -> Result<UserForAuth, model::Error> {
+        let sb = sqlb::select()
+            .table(Self::TABLE)
+            .columns(Self::AUTH_COLUMNS)
+            .and_where_eq("username", username);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, username)
+    }
+
+    /// update - update a user (e.g. new password hash)
+    pub async fn update(db: &Db, id: i64, data: UserPatch) -> Result<User, model::Error> {
+        let sb = sqlb::update()
+            .table(Self::TABLE)
+            .data(data.fields())
+            .and_where_eq("id", id)
+            .returning(Self::COLUMNS);
+
+        let result = sb.fetch_one(db).await;
+
+        handle_fetch_one_result(result, Self::TABLE, &id.to_string())
+    }
+}
+// endregion: UserMac
+
+// region:    Utils
+/// handle_fetch_one_result - handle sqlx::Error
+fn handle_fetch_one_result<T>(
+    result: Result<T, sqlx::Error>,
+    typ: &'static str,
+    key: &str,
+) -> Result<T, model::Error> {
+    result.map_err(|sqlx_error| match sqlx_error {
+        sqlx::Error::RowNotFound => model::Error::EntityNotFound(typ, key.to_string()),
+        other => model::Error::Sqlx(other),
+    })
+}
+// endregion: Utils
+
+#[cfg(test)]
+#[path = "../_tests/model_user.rs"]
+mod tests;
@@ -1,22 +1,271 @@
-use crate::model::Db;
+use crate::config::SecurityConfig;
+use crate::model::{self, Db, SessionMac};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error as ThisError;
 
+const SESSION_DURATION_SEC: i64 = 60 * 60 * 24;
+// token signature key (security.token_key), set once at startup
+static TOKEN_KEY: OnceLock<String> = OnceLock::new();
+// dev db and unit tests only (the config requires a token_key otherwise)
+const DEV_TOKEN_KEY: &str = "dev-only-token-key";
+
+type HmacSha256 = Hmac<Sha256>;
+
 /// User Context
 pub struct UserCtx {
     pub user_id: i64,
 }
+
 /// Get UserCtx from token
-pub async fn utx_from_token(_db: &Db, token: &str) -> Result<UserCtx, Error> {
-    // TODO: real validation needed
-    // for now, just parse to i64
-    match token.parse::<i64>() {
-        Ok(user_id) => Ok(UserCtx { user_id }),
-        Err(_) => Err(Error::InvalidToken(token.to_string())),
+pub async fn utx_from_token(db: &Db, token: &str) -> Result<UserCtx, Error> {
+    let claims = validate_token(db, token).await?;
+
+    Ok(UserCtx {
+        user_id: claims.user_id,
+    })
+}
+
+/// Set the token signature key of the process (the dev key if none, checked by serve)
+pub fn init_token_key(config: &SecurityConfig) {
+    let key = match &config.token_key {
+        Some(key) => key.clone(),
+        None => {
+            tracing::warn!("no security.token_key, the tokens are signed with the dev key");
+            DEV_TOKEN_KEY.to_string()
+        }
+    };
+    if TOKEN_KEY.set(key).is_err() {
+        tracing::warn!("token key already set");
+    }
+}
+
+// region:    Sessions
+/// Create a new session for a user and return its token
+pub async fn new_session_token(db: &Db, user_id: i64) -> Result<String, Error> {
+    let exp = now_sec() + SESSION_DURATION_SEC;
+    let session = SessionMac::create(db, user_id, exp).await?;
+
+    let claims = TokenClaims {
+        user_id,
+        session_id: session.id,
+        exp,
+    };
+
+    Ok(claims.sign())
+}
+
+/// Revoke the session of a token
+pub async fn end_session(db: &Db, token: &str) -> Result<(), Error> {
+    let claims = validate_token(db, token).await?;
+    SessionMac::revoke(db, claims.session_id).await?;
+
+    Ok(())
+}
+
+/// Revoke the session of a token and return the token of a new session (both or none),
+/// fails with TokenRevoked if the session was revoked meanwhile (e.g. a concurrent refresh)
+pub async fn rotate_session(db: &Db, token: &str) -> Result<String, Error> {
+    let claims = validate_token(db, token).await?;
+
+    let exp = now_sec() + SESSION_DURATION_SEC;
+    let session = match SessionMac::rotate(db, claims.session_id, exp).await {
+        Ok(session) => session,
+        Err(model::Error::EntityNotFound(_, _)) => return Err(Error::TokenRevoked),
+        Err(ex) => return Err(Error::Model(ex)),
+    };
+
+    let claims = TokenClaims {
+        user_id: session.user_id,
+        session_id: session.id,
+        exp,
+    };
+
+    Ok(claims.sign())
+}
+
+/// Validate the token signature, expiration, and session revocation
+async fn validate_token(db: &Db, token: &str) -> Result<TokenClaims, Error> {
+    let claims = TokenClaims::verify(token)?;
+
+    if claims.exp <= now_sec() {
+        return Err(Error::TokenExpired);
+    }
+
+    let session = match SessionMac::get(db, claims.session_id).await {
+        Ok(session) => session,
+        Err(model::Error::EntityNotFound(_, _)) => return Err(Error::TokenRevoked),
+        Err(ex) => return Err(Error::Model(ex)),
+    };
+    // the claims must be the ones of the session (e.g. not a forged far-future exp)
+    if session.revoked || session.user_id != claims.user_id || session.exp != claims.exp {
+        return Err(Error::TokenRevoked);
+    }
+
+    Ok(claims)
+}
+// endregion: Sessions
+
+// region:    Token
+/// Token Claims - signed as `user_id.session_id.exp.signature`
+struct TokenClaims {
+    user_id: i64,
+    session_id: i64,
+    exp: i64, // expiration (unix seconds)
+}
+
+impl TokenClaims {
+    /// Content to be signed
+    fn content(&self) -> String {
+        format!("{}.{}.{}", self.user_id, self.session_id, self.exp)
+    }
+
+    /// Signed token string
+    fn sign(&self) -> String {
+        let content = self.content();
+        let mut mac = new_mac();
+        mac.update(content.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+        format!("{}.{}", content, signature)
+    }
+
+    /// Parse a token string and verify its signature
+    fn verify(token: &str) -> Result<Self, Error> {
+        let (content, signature) = token.rsplit_once('.').ok_or(Error::TokenTampered)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| Error::TokenTampered)?;
+
+        let mut mac = new_mac();
+        mac.update(content.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| Error::TokenTampered)?;
+
+        let parts: Vec<i64> = content
+            .split('.')
+            .map(|part| part.parse::<i64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| Error::TokenTampered)?;
+        match parts[..] {
+            [user_id, session_id, exp] => Ok(TokenClaims {
+                user_id,
+                session_id,
+                exp,
+            }),
+            _ => Err(Error::TokenTampered),
+        }
     }
 }
 
+/// New HMAC with the token key
+fn new_mac() -> HmacSha256 {
+    let key = match TOKEN_KEY.get() {
+        Some(key) => key.as_str(),
+        None if cfg!(test) => DEV_TOKEN_KEY,
+        None => panic!("token key not set, security::init_token_key must be called at startup"),
+    };
+    // Note: HMAC accepts keys of any size
+    HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC can take key of any size")
+}
+
+/// Current time in unix seconds
+fn now_sec() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+// endregion: Token
+
+// region:    Passwords
+/// Hash a clear password with argon2
+pub fn hash_pwd(pwd: &str) -> Result<String, Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(pwd.as_bytes(), &salt)
+        .map_err(|ex| Error::PwdHash(ex.to_string()))?;
+
+    Ok(hash.to_string())
+}
+
+/// Verify a clear password against an argon2 hash
+pub fn verify_pwd(pwd: &str, pwd_hash: &str) -> Result<(), Error> {
+    let parsed_hash = PasswordHash::new(pwd_hash).map_err(|ex| Error::PwdHash(ex.to_string()))?;
+
+    Argon2::default()
+        .verify_password(pwd.as_bytes(), &parsed_hash)
+        .map_err(|_| Error::FailLogin)
+}
+
+/// Hash a clear password on the blocking thread pool (argon2 would stall the runtime worker)
+pub async fn hash_pwd_blocking(pwd: String) -> Result<String, Error> {
+    tokio::task::spawn_blocking(move || hash_pwd(&pwd))
+        .await
+        .map_err(|ex| Error::PwdHash(ex.to_string()))?
+}
+
+/// Verify a clear password on the blocking thread pool, against the dummy hash if no user
+/// hash (an unknown username takes as long as a wrong password, and always fails)
+pub async fn verify_pwd_blocking(pwd: String, pwd_hash: Option<String>) -> Result<(), Error> {
+    let found = pwd_hash.is_some();
+    tokio::task::spawn_blocking(move || {
+        let pwd_hash = match &pwd_hash {
+            Some(pwd_hash) => pwd_hash.as_str(),
+            None => dummy_pwd_hash()?,
+        };
+        verify_pwd(&pwd, pwd_hash)
+    })
+    .await
+    .map_err(|ex| Error::PwdHash(ex.to_string()))??;
+
+    if found {
+        Ok(())
+    } else {
+        Err(Error::FailLogin)
+    }
+}
+
+/// Argon2 hash of a random password, same params as the user hashes (computed once)
+fn dummy_pwd_hash() -> Result<&'static str, Error> {
+    static DUMMY_PWD_HASH: OnceLock<String> = OnceLock::new();
+    if let Some(pwd_hash) = DUMMY_PWD_HASH.get() {
+        return Ok(pwd_hash);
+    }
+    let pwd = SaltString::generate(&mut OsRng);
+    let pwd_hash = hash_pwd(pwd.as_str())?;
+    Ok(DUMMY_PWD_HASH.get_or_init(|| pwd_hash))
+}
+// endregion: Passwords
+
 #[derive(ThisError, Debug)]
 pub enum Error {
-    #[error("Invalid token {0}")]
-    InvalidToken(String),
+    #[error("Token expired")]
+    TokenExpired,
+
+    #[error("Token signature invalid")]
+    TokenTampered,
+
+    #[error("Token revoked")]
+    TokenRevoked,
+
+    #[error("Wrong username or password")]
+    FailLogin,
+
+    #[error("Password hash error {0}")]
+    PwdHash(String),
+
+    #[error(transparent)]
+    Model(#[from] model::Error),
 }
+
+#[cfg(test)]
+#[path = "../_tests/security.rs"]
+mod tests;
@@ -0,0 +1,92 @@
+use super::{filter_auth::do_auth, filter_utils::with_db, json_response, metrics::route};
+use crate::{
+    model::{Budget, BudgetMac, Db},
+    security::UserCtx,
+};
+use std::sync::Arc;
+use warp::{reply::Json, Filter};
+
+/// budget REST API
+pub fn budget_rest_filters(
+    base_path: &'static str,
+    db: Arc<Db>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let budget_path = warp::path(base_path).and(warp::path("budget")); // /api/budget
+    let list_budget_path = warp::path(base_path).and(warp::path("lists")); // /api/lists/10/budget
+    let common = with_db(db.clone()).and(do_auth(db));
+
+    // GET user budget `GET /budget`
+    let user_get = budget_path
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(route(format!("/{}/budget", base_path)))
+        .and(common.clone())
+        .and_then(budget_user_get);
+
+    // SET user budget `PUT /budget with body Budget`
+    let user_set = budget_path
+        .and(warp::put())
+        .and(warp::path::end())
+        .and(route(format!("/{}/budget", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(budget_user_set);
+
+    // GET shopping list budget `GET /lists/10/budget`
+    let list_get = list_budget_path
+        .and(warp::get())
+        .and(warp::path::param())
+        .and(warp::path("budget"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/budget", base_path)))
+        .and(common.clone())
+        .and_then(budget_list_get);
+
+    // SET shopping list budget `PUT /lists/10/budget with body Budget`
+    let list_set = list_budget_path
+        .and(warp::put())
+        .and(warp::path::param())
+        .and(warp::path("budget"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/budget", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(budget_list_set);
+
+    user_get.or(user_set).or(list_get).or(list_set)
+}
+
+/// GET - `budget`
+async fn budget_user_get(db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let budget = BudgetMac::get_for_user(&db, &utx).await?;
+    json_response(budget)
+}
+
+/// PUT - `budget` with body `Budget`
+async fn budget_user_set(db: Arc<Db>, utx: UserCtx, data: Budget) -> Result<Json, warp::Rejection> {
+    let budget = BudgetMac::set_for_user(&db, &utx, data).await?;
+    json_response(budget)
+}
+
+/// GET - `lists/10/budget`
+async fn budget_list_get(list_id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let budget = BudgetMac::get_for_list(&db, &utx, list_id).await?;
+    json_response(budget)
+}
+
+/// PUT - `lists/10/budget` with body `Budget`
+async fn budget_list_set(
+    list_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+    data: Budget,
+) -> Result<Json, warp::Rejection> {
+    let budget = BudgetMac::set_for_list(&db, &utx, list_id, data).await?;
+    json_response(budget)
+}
+
+// region:    Test
+#[cfg(test)]
+#[path = "../_tests/web_budget.rs"]
+mod tests;
+// endregion: Test
@@ -0,0 +1,106 @@
+use super::{filter_auth::do_auth, filter_utils::with_db, json_response, metrics::route};
+use crate::{
+    model::{CategoryMac, CategoryPatch, Db},
+    security::UserCtx,
+};
+use std::sync::Arc;
+use warp::{reply::Json, Filter};
+
+/// category REST API
+pub fn category_rest_filters(
+    base_path: &'static str,
+    db: Arc<Db>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let categories_path = warp::path(base_path).and(warp::path("categories")); // /api/categories
+    let common = with_db(db.clone()).and(do_auth(db));
+
+    // LIST categories `GET categories/`
+    let list = categories_path
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(route(format!("/{}/categories", base_path)))
+        .and(common.clone())
+        .and_then(category_list);
+
+    // GET category `GET /categories/20`
+    let get = categories_path
+        .and(warp::get())
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/categories/:id", base_path)))
+        .and(common.clone())
+        .and_then(category_get);
+
+    // CREATE category `POST /categories with body CategoryPatch`
+    let create = categories_path
+        .and(warp::post())
+        .and(warp::path::end())
+        .and(route(format!("/{}/categories", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(category_create);
+
+    // UPDATE category `PATCH /categories/20 with body CategoryPatch`
+    let update = categories_path
+        .and(warp::patch())
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/categories/:id", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(category_update);
+
+    // DELETE category `DELETE /categories/20`
+    let delete = categories_path
+        .and(warp::delete())
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/categories/:id", base_path)))
+        .and(common.clone())
+        .and_then(category_delete);
+
+    list.or(get).or(create).or(update).or(delete)
+}
+
+/// GET - `categories/`
+async fn category_list(db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let categories = CategoryMac::list(&db, &utx).await?;
+    json_response(categories)
+}
+
+/// GET - `categories/20`
+async fn category_get(id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let category = CategoryMac::get(&*db, &utx, id).await?;
+    json_response(category)
+}
+
+/// CREATE - `categories/` with body `CategoryPatch`
+async fn category_create(
+    db: Arc<Db>,
+    utx: UserCtx,
+    patch: CategoryPatch,
+) -> Result<Json, warp::Rejection> {
+    let category = CategoryMac::create(&db, &utx, patch).await?;
+    json_response(category)
+}
+
+/// PATCH - `categories/20` with body `CategoryPatch`
+async fn category_update(
+    id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+    patch: CategoryPatch,
+) -> Result<Json, warp::Rejection> {
+    let category = CategoryMac::update(&db, &utx, id, patch).await?;
+    json_response(category)
+}
+
+/// DELETE - `categories/20`
+async fn category_delete(id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let category = CategoryMac::delete(&db, &utx, id).await?;
+    json_response(category)
+}
+
+#[cfg(test)]
+#[path = "../_tests/web_category.rs"]
+mod tests;
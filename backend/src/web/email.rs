@@ -0,0 +1,71 @@
+use super::{
+    filter_auth::do_auth,
+    filter_utils::{with_db, with_mailer},
+    json_response,
+    metrics::route,
+};
+use crate::{
+    mail::{render_shopping_list, Mailer},
+    model::{Db, GroceryMac, ListMemberMac, ShoppingListMac},
+    security::UserCtx,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use warp::{reply::Json, Filter};
+
+/// EmailRequest - body of a shopping list email
+#[derive(Debug, Deserialize)]
+pub struct EmailRequest {
+    pub to: String,
+}
+
+/// email REST API
+pub fn email_rest_filters(
+    base_path: &'static str,
+    db: Arc<Db>,
+    mailer: Arc<Mailer>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let lists_path = warp::path(base_path).and(warp::path("lists")); // /api/lists
+    let common = with_db(db.clone()).and(do_auth(db));
+
+    // EMAIL shopping list `POST /lists/10/email with body EmailRequest`
+    lists_path
+        .and(warp::post())
+        .and(warp::path::param())
+        .and(warp::path("email"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/email", base_path)))
+        .and(common)
+        .and(with_mailer(mailer))
+        .and(warp::body::json())
+        .and_then(shopping_list_email)
+}
+
+/// POST - `lists/10/email` (owner or editor, rate limited per user)
+async fn shopping_list_email(
+    id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+    mailer: Arc<Mailer>,
+    data: EmailRequest,
+) -> Result<Json, warp::Rejection> {
+    // Note: a viewer cannot send, otherwise any invited user could relay mails to any address
+    ListMemberMac::require_edit(&*db, &utx, id).await?;
+
+    let shopping_list = ShoppingListMac::get(&db, &utx, id).await?;
+    let groceries = GroceryMac::list_by_list_id(&db, &utx, id).await?;
+    let summary = GroceryMac::summary(&db, &utx, Some(id)).await?;
+
+    let (subject, text, html) = render_shopping_list(&shopping_list, &groceries, &summary);
+    let email = mailer.message(&data.to, &subject, text, html)?;
+    // Note: only a valid email counts in the rate limit, right before it is sent
+    mailer.check_rate_limit(utx.user_id)?;
+    mailer.send_message(email).await?;
+
+    json_response(json!({ "to": data.to }))
+}
+
+#[cfg(test)]
+#[path = "../_tests/web_email.rs"]
+mod tests;
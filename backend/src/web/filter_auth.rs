@@ -1,3 +1,4 @@
+use super::filter_log::record_user_id;
 use super::filter_utils::with_db;
 use crate::model::Db;
 use crate::security::{utx_from_token, UserCtx};
@@ -5,7 +6,7 @@ use crate::web::Error;
 use std::sync::Arc;
 use warp::{Filter, Rejection};
 
-const HEADER_XAUTH: &str = "X-Auth-Token";
+pub const HEADER_XAUTH: &str = "X-Auth-Token";
 
 /// auth filter
 pub fn do_auth(db: Arc<Db>) -> impl Filter<Extract = (UserCtx,), Error = Rejection> + Clone {
@@ -16,6 +17,7 @@ pub fn do_auth(db: Arc<Db>) -> impl Filter<Extract = (UserCtx,), Error = Rejecti
             match xauth {
                 Some(xauth) => {
                     let utx = utx_from_token(&db, &xauth).await?;
+                    record_user_id(utx.user_id);
                     Ok::<UserCtx, Rejection>(utx)
                 }
                 None => Err(Error::FailAuthMissingXAuth.into()),
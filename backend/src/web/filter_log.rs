@@ -0,0 +1,53 @@
+use super::metrics::observe_request;
+use rand::Rng;
+use tracing::{field, Span};
+
+pub const HEADER_REQUEST_ID: &str = "X-Request-Id";
+
+/// request span - every event of a request carries its id, method and path
+/// (the user id is recorded by the auth filter once known)
+pub fn request_span(info: warp::trace::Info) -> Span {
+    let request_id = request_id(info.request_headers().get(HEADER_REQUEST_ID));
+    tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %info.method(),
+        path = %info.path(),
+        user_id = field::Empty,
+    )
+}
+
+/// log hook - one event per request, with its status and latency (inside the request span),
+/// and the request metrics
+pub fn log_request(info: warp::log::Info) {
+    let status = info.status();
+    observe_request(info.method().as_str(), status.as_u16(), info.elapsed());
+
+    let latency_ms = info.elapsed().as_secs_f64() * 1000.0;
+    if status.is_server_error() {
+        tracing::error!(status = status.as_u16(), latency_ms, "request");
+    } else if status.is_client_error() {
+        tracing::warn!(status = status.as_u16(), latency_ms, "request");
+    } else {
+        tracing::info!(status = status.as_u16(), latency_ms, "request");
+    }
+}
+
+/// Record the user id on the current request span
+pub fn record_user_id(user_id: i64) {
+    Span::current().record("user_id", user_id);
+}
+
+/// Request id from the caller (e.g. a proxy) when valid, otherwise a new random one
+pub fn request_id(header: Option<&warp::http::HeaderValue>) -> String {
+    header
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= 64)
+        .filter(|id| id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| format!("{:016x}", rand::thread_rng().gen::<u64>()))
+}
+
+#[cfg(test)]
+#[path = "../_tests/web_filter_log.rs"]
+mod tests;
@@ -1,3 +1,4 @@
+use crate::mail::Mailer;
 use crate::model::Db;
 use std::convert::Infallible;
 use std::sync::Arc;
@@ -7,3 +8,10 @@ use warp::Filter;
 pub fn with_db(db: Arc<Db>) -> impl Filter<Extract = (Arc<Db>,), Error = Infallible> + Clone {
     warp::any().map(move || db.clone())
 }
+
+/// with_mailer - warp filter for injecting the mailer
+pub fn with_mailer(
+    mailer: Arc<Mailer>,
+) -> impl Filter<Extract = (Arc<Mailer>,), Error = Infallible> + Clone {
+    warp::any().map(move || mailer.clone())
+}
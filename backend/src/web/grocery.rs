@@ -1,13 +1,40 @@
-use super::{filter_auth::do_auth, filter_utils::with_db};
+use super::{
+    filter_auth::do_auth, filter_utils::with_db, json_page_response, json_response, metrics::route,
+};
 use crate::{
-    model::{Db, GroceryMac, GroceryPatch},
+    model::{BudgetMac, Db, GroceryListOptions, GroceryMac, GroceryOp, GroceryPatch},
     security::UserCtx,
 };
-use serde::Serialize;
-use serde_json::json;
+use serde::Deserialize;
 use std::sync::Arc;
 use warp::{reply::Json, Filter};
 
+/// Reorder - body of a grocery reorder
+#[derive(Debug, Deserialize)]
+pub struct Reorder {
+    pub ids: Vec<i64>,
+}
+
+/// Batch - body of a grocery batch (applied in order, all or nothing)
+#[derive(Debug, Deserialize)]
+pub struct Batch {
+    pub ops: Vec<GroceryOp>,
+}
+
+/// Search Query - `?q=yogurt&limit=10`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+/// Summary Query - `?list_id=10` to summarize a single shopping list
+#[derive(Debug, Default, Deserialize)]
+pub struct SummaryQuery {
+    pub list_id: Option<i64>,
+}
+
 /// grocery REST API
 pub fn grocery_rest_filters(
     base_path: &'static str,
@@ -16,53 +43,148 @@ pub fn grocery_rest_filters(
     let groceries_path = warp::path(base_path).and(warp::path("groceries")); // /api/groceries
     let common = with_db(db.clone()).and(do_auth(db.clone()));
 
-    // LIST groceries `GET groceries/`
+    // LIST groceries `GET groceries/?status=shelf&name=rice&cost_min=10&sort=name&limit=20&cursor=..`
     let list = groceries_path
         .and(warp::get())
         .and(warp::path::end())
+        .and(route(format!("/{}/groceries", base_path)))
         .and(common.clone())
+        .and(warp::query::<GroceryListOptions>())
         .and_then(grocery_list);
 
+    // SUMMARY of groceries `GET /groceries/summary`
+    let summary = groceries_path
+        .and(warp::get())
+        .and(warp::path("summary"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/groceries/summary", base_path)))
+        .and(common.clone())
+        .and(warp::query::<SummaryQuery>())
+        .and_then(grocery_summary);
+
+    // LIST groceries by category `GET /groceries/by-category` (in the store aisles order)
+    let list_grouped = groceries_path
+        .and(warp::get())
+        .and(warp::path("by-category"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/groceries/by-category", base_path)))
+        .and(common.clone())
+        .and_then(grocery_list_grouped);
+
+    // SEARCH groceries `GET /groceries/search?q=yogurt` (best match first)
+    let search = groceries_path
+        .and(warp::get())
+        .and(warp::path("search"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/groceries/search", base_path)))
+        .and(common.clone())
+        .and(warp::query::<SearchQuery>())
+        .and_then(grocery_search);
+
     // GET grocery `GET /groceries/100`
     let get = groceries_path
         .and(warp::get())
-        .and(common.clone())
         .and(warp::path::param())
+        .and(route(format!("/{}/groceries/:id", base_path)))
+        .and(common.clone())
         .and_then(grocery_get);
 
+    // BATCH of grocery operations `POST /groceries/batch with body Batch`
+    let batch = groceries_path
+        .and(warp::post())
+        .and(warp::path("batch"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/groceries/batch", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(grocery_batch);
+
     // CREATE grocery `POST /groceries with body GroceryPatch`
     let create = groceries_path
         .and(warp::post())
+        .and(warp::path::end())
+        .and(route(format!("/{}/groceries", base_path)))
         .and(common.clone())
         .and(warp::body::json())
         .and_then(grocery_create);
 
+    // REORDER groceries `PATCH /groceries/reorder with body Reorder`
+    let reorder = groceries_path
+        .and(warp::patch())
+        .and(warp::path("reorder"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/groceries/reorder", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(grocery_reorder);
+
     // UPDATE grocery `PATCH /groceries/100 with body GroceryPatch`
     let update = groceries_path
         .and(warp::patch())
-        .and(common.clone())
         .and(warp::path::param())
+        .and(route(format!("/{}/groceries/:id", base_path)))
+        .and(common.clone())
         .and(warp::body::json())
         .and_then(grocery_update);
 
     // DELETE grocery `DELETE /groceries/100`
     let delete = groceries_path
         .and(warp::delete())
-        .and(common.clone())
         .and(warp::path::param())
+        .and(route(format!("/{}/groceries/:id", base_path)))
+        .and(common.clone())
         .and_then(grocery_delete);
 
-    list.or(get).or(create).or(update).or(delete)
+    list.or(summary)
+        .or(list_grouped)
+        .or(search)
+        .or(get)
+        .or(batch)
+        .or(create)
+        .or(reorder)
+        .or(update)
+        .or(delete)
+}
+
+/// GET - `groceries/` (a page with the `next` cursor when `limit`)
+async fn grocery_list(
+    db: Arc<Db>,
+    utx: UserCtx,
+    options: GroceryListOptions,
+) -> Result<Json, warp::Rejection> {
+    let groceries = GroceryMac::list(&db, &utx, &options).await?;
+    let next = options.next_cursor(&groceries);
+    json_page_response(groceries, next)
+}
+
+/// GET - `groceries/by-category`
+async fn grocery_list_grouped(db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let groups = GroceryMac::list_grouped(&db, &utx).await?;
+    json_response(groups)
 }
 
-/// GET - `groceries/`
-async fn grocery_list(db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
-    let groceries = GroceryMac::list(&db, &utx).await?;
+/// GET - `groceries/search?q=yogurt`
+async fn grocery_search(
+    db: Arc<Db>,
+    utx: UserCtx,
+    query: SearchQuery,
+) -> Result<Json, warp::Rejection> {
+    let groceries = GroceryMac::search(&db, &utx, &query.q, query.limit).await?;
     json_response(groceries)
 }
 
+/// GET - `groceries/summary`
+async fn grocery_summary(
+    db: Arc<Db>,
+    utx: UserCtx,
+    query: SummaryQuery,
+) -> Result<Json, warp::Rejection> {
+    let summary = GroceryMac::summary(&db, &utx, query.list_id).await?;
+    json_response(summary)
+}
+
 /// GET - `groceries/100`
-async fn grocery_get(db: Arc<Db>, utx: UserCtx, id: i64) -> Result<Json, warp::Rejection> {
+async fn grocery_get(id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
     let grocery = GroceryMac::get(&db, &utx, id).await?;
     json_response(grocery)
 }
@@ -74,34 +196,44 @@ async fn grocery_create(
     patch: GroceryPatch,
 ) -> Result<Json, warp::Rejection> {
     let grocery = GroceryMac::create(&db, &utx, patch).await?;
+    let grocery = BudgetMac::with_status(&db, grocery).await?;
     json_response(grocery)
 }
 
 /// PATCH - `groceries/100` with body `GroceryPatch`
 async fn grocery_update(
+    id: i64,
     db: Arc<Db>,
     utx: UserCtx,
-    id: i64,
     patch: GroceryPatch,
 ) -> Result<Json, warp::Rejection> {
     let grocery = GroceryMac::update(&db, &utx, id, patch).await?;
+    let grocery = BudgetMac::with_status(&db, grocery).await?;
     json_response(grocery)
 }
 
+/// PATCH - `groceries/reorder` with body `Reorder`
+async fn grocery_reorder(
+    db: Arc<Db>,
+    utx: UserCtx,
+    reorder: Reorder,
+) -> Result<Json, warp::Rejection> {
+    let groceries = GroceryMac::reorder(&db, &utx, reorder.ids).await?;
+    json_response(groceries)
+}
+
+/// POST - `groceries/batch` with body `Batch` (the result of each operation)
+async fn grocery_batch(db: Arc<Db>, utx: UserCtx, batch: Batch) -> Result<Json, warp::Rejection> {
+    let results = GroceryMac::batch(&db, &utx, batch.ops).await?;
+    json_response(results)
+}
+
 /// DELETE - `groceries/100`
-async fn grocery_delete(db: Arc<Db>, utx: UserCtx, id: i64) -> Result<Json, warp::Rejection> {
+async fn grocery_delete(id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
     let grocery = GroceryMac::delete(&db, &utx, id).await?;
     json_response(grocery)
 }
 
-// region:    Utils
-/// json_response
-fn json_response<D: Serialize>(data: D) -> Result<Json, warp::Rejection> {
-    let response = json!({ "data": data });
-    Ok(warp::reply::json(&response))
-}
-// endregion: Utils
-
 // region:    Test
 #[cfg(test)]
 #[path = "../_tests/web_grocery.rs"]
@@ -0,0 +1,59 @@
+use super::{filter_utils::with_db, metrics::route};
+use crate::model::{self, Db, ExpectedMigration};
+use serde_json::json;
+use std::{convert::Infallible, sync::Arc};
+use warp::http::StatusCode;
+use warp::{reply::Reply, Filter};
+
+/// health REST API (no auth, for the load balancers and container healthchecks)
+pub fn health_filters(
+    db: Arc<Db>,
+    migrations: Arc<Vec<ExpectedMigration>>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    // LIVENESS `GET /healthz` (the process is up)
+    let healthz = warp::path("healthz")
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(route("/healthz"))
+        .and_then(health_live);
+
+    // READINESS `GET /readyz` (the db is reachable and migrated)
+    let readyz = warp::path("readyz")
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(route("/readyz"))
+        .and(with_db(db))
+        .and(warp::any().map(move || migrations.clone()))
+        .and_then(health_ready);
+
+    healthz.or(readyz)
+}
+
+async fn health_live() -> Result<impl Reply, Infallible> {
+    Ok(warp::reply::json(&json!({ "status": "ok" })))
+}
+
+async fn health_ready(
+    db: Arc<Db>,
+    migrations: Arc<Vec<ExpectedMigration>>,
+) -> Result<impl Reply, Infallible> {
+    let (status, body) = match model::check_ready(&db, &migrations).await {
+        Ok(version) => (
+            StatusCode::OK,
+            json!({ "status": "ready", "migration": version }),
+        ),
+        Err(ex) => {
+            tracing::warn!(error = %ex, "not ready");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                json!({ "status": "not ready", "reason": ex.to_string() }),
+            )
+        }
+    };
+
+    Ok(warp::reply::with_status(warp::reply::json(&body), status))
+}
+
+#[cfg(test)]
+#[path = "../_tests/web_health.rs"]
+mod tests;
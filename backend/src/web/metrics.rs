@@ -0,0 +1,160 @@
+use super::filter_utils::with_db;
+use crate::model::Db;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::{
+    cell::RefCell, convert::Infallible, future::Future, sync::Arc, sync::LazyLock, time::Duration,
+};
+use warp::{reply::Reply, Filter};
+
+tokio::task_local! {
+    // route label of the request being served, set by the filter of its matched route
+    static ROUTE: RefCell<Option<Arc<str>>>;
+}
+
+/// Metrics of the web server (one registry per process)
+struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    latency: HistogramVec,
+    rejections: IntCounterVec,
+    db_pool: IntGaugeVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+impl Metrics {
+    fn new() -> Metrics {
+        let requests = IntCounterVec::new(
+            Opts::new(
+                "http_requests_total",
+                "Requests by route, method and status",
+            ),
+            &["route", "method", "status"],
+        )
+        .expect("valid metric");
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Request latency by route and method",
+            ),
+            &["route", "method"],
+        )
+        .expect("valid metric");
+        let rejections = IntCounterVec::new(
+            Opts::new("http_rejections_total", "Rejected requests by error type"),
+            &["typ"],
+        )
+        .expect("valid metric");
+        let db_pool = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Db pool connections by state"),
+            &["state"],
+        )
+        .expect("valid metric");
+
+        let registry = Registry::new();
+        registry
+            .register(Box::new(requests.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(latency.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(rejections.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(db_pool.clone()))
+            .expect("unique metric");
+
+        Metrics {
+            registry,
+            requests,
+            latency,
+            rejections,
+            db_pool,
+        }
+    }
+}
+
+/// metrics REST API `GET /metrics` (prometheus text format, no auth)
+pub fn metrics_filters(
+    db: Arc<Db>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("metrics")
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(route("/metrics"))
+        .and(with_db(db))
+        .and_then(metrics_get)
+}
+
+async fn metrics_get(db: Arc<Db>) -> Result<impl Reply, Infallible> {
+    // db pool gauges, as of the scrape
+    let size = db.size() as i64;
+    let idle = db.num_idle() as i64;
+    let db_pool = &METRICS.db_pool;
+    db_pool.with_label_values(&["size"]).set(size);
+    db_pool.with_label_values(&["idle"]).set(idle);
+    db_pool.with_label_values(&["in_use"]).set(size - idle);
+
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(ex) = encoder.encode(&METRICS.registry.gather(), &mut body) {
+        tracing::error!(error = %ex, "cannot encode the metrics");
+    }
+
+    Ok(warp::reply::with_header(
+        body,
+        "content-type",
+        encoder.format_type(),
+    ))
+}
+
+/// Filter setting the route label of the request (e.g. `/api/groceries/:id`),
+/// to put in each route once its path is matched
+pub fn route(label: impl Into<Arc<str>>) -> impl Filter<Extract = (), Error = Infallible> + Clone {
+    let label: Arc<str> = label.into();
+    warp::any()
+        .map(move || {
+            // Note: no route label outside of route_scope (e.g. the warp::test requests)
+            let _ = ROUTE.try_with(|route| *route.borrow_mut() = Some(label.clone()));
+        })
+        .untuple_one()
+}
+
+/// Serve a request with its own route label (`other` until a route filter sets it)
+pub fn route_scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    ROUTE.scope(RefCell::new(None), future)
+}
+
+/// Route label of the current request, `other` if no route matched (e.g. unknown paths)
+pub fn route_label() -> Arc<str> {
+    ROUTE
+        .try_with(|route| route.borrow().clone())
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| Arc::from("other"))
+}
+
+/// Count a request (of the current route) and observe its latency
+pub fn observe_request(method: &str, status: u16, elapsed: Duration) {
+    let route = route_label();
+    METRICS
+        .requests
+        .with_label_values(&[&route, method, &status.to_string()])
+        .inc();
+    METRICS
+        .latency
+        .with_label_values(&[&route, method])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Count a rejection by its error type (`WebErrorMessage.typ`, or `warp`)
+pub fn observe_rejection(typ: &str) {
+    METRICS.rejections.with_label_values(&[typ]).inc();
+}
+
+#[cfg(test)]
+#[path = "../_tests/web_metrics.rs"]
+mod tests;
@@ -1,62 +1,207 @@
 use crate::{
-    model::{self, Db},
+    config::WebConfig,
+    mail::{self, Mailer},
+    model::{self, Db, ExpectedMigration},
     security,
 };
+use budget::budget_rest_filters;
+use category::category_rest_filters;
+use email::email_rest_filters;
 use grocery::grocery_rest_filters;
+use health::health_filters;
+use metrics::{metrics_filters, route};
+use serde::Serialize;
 use serde_json::json;
-use std::{convert::Infallible, path::Path, sync::Arc};
-use warp::{reject::Rejection, reply::Reply, Filter};
+use shopping_list::shopping_list_rest_filters;
+use std::{convert::Infallible, future::Future, net::SocketAddr, path::Path, sync::Arc};
+use user::user_rest_filters;
+use warp::http::StatusCode;
+use warp::hyper::service::{make_service_fn, service_fn, Service};
+use warp::hyper::Server;
+use warp::{reject::Rejection, reply::Json, reply::Reply, Filter};
 
+mod budget;
+mod category;
+mod email;
 mod filter_auth;
+mod filter_log;
 mod filter_utils;
 mod grocery;
+mod health;
+mod metrics;
+mod shopping_list;
+mod user;
 
-/// start web server
-pub async fn start_web(web_folder: &str, web_port: u16, db: Arc<Db>) -> Result<(), Error> {
+/// start web server, until `shutdown` resolves (then drains the in-flight requests)
+pub async fn start_web(
+    config: &WebConfig,
+    db: Arc<Db>,
+    mailer: Arc<Mailer>,
+    migrations: Arc<Vec<ExpectedMigration>>, // for the readiness checks
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), Error> {
     // validate the web_folder
+    let web_folder = config.folder.as_str();
     if !Path::new(web_folder).exists() {
         return Err(Error::FailStartWebFolderNotFound(web_folder.to_string()));
     }
 
     // Apis
-    let apis = grocery_rest_filters("api", db);
+    let apis = user_rest_filters("api", db.clone())
+        .or(shopping_list_rest_filters("api", db.clone()))
+        .or(budget_rest_filters("api", db.clone()))
+        .or(category_rest_filters("api", db.clone()))
+        .or(email_rest_filters("api", db.clone(), mailer))
+        .or(grocery_rest_filters("api", db.clone()));
 
     // Static content
     let content = warp::fs::dir(web_folder.to_string());
     let root_index = warp::get()
         .and(warp::path::end())
         .and(warp::fs::file(format!("{}/index.html", web_folder)));
-    let static_site = content.or(root_index);
+    let static_site = content.or(root_index).and(route("static"));
 
-    // Combine all routes
-    let routes = apis.or(static_site).recover(handle_rejection);
+    // Health (liveness and readiness probes) and metrics
+    let health = health_filters(db.clone(), migrations).or(metrics_filters(db));
 
-    println!("Start 127.0.0.1:{} at {}", web_port, web_folder);
-    warp::serve(routes).run(([127, 0, 0, 1], web_port)).await;
+    // Combine all routes (each request in its own span, and logged with its status and latency)
+    let routes = apis
+        .or(health)
+        .or(static_site)
+        .recover(handle_rejection)
+        .with(warp::log::custom(filter_log::log_request))
+        .with(warp::trace(filter_log::request_span));
+
+    // Serve (as warp::serve), each request with its own route label for the metrics
+    let service = warp::service(routes);
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let mut service = service.clone();
+                metrics::route_scope(service.call(req))
+            }))
+        }
+    });
+    let bind_addr = SocketAddr::new(config.host, config.port);
+    let server = Server::try_bind(&bind_addr)
+        .map_err(|ex| Error::FailStartBind(bind_addr.to_string(), ex.to_string()))?
+        .serve(make_service);
+    let addr = server.local_addr();
+
+    tracing::info!(%addr, web_folder, "web server started");
+    if let Err(ex) = server.with_graceful_shutdown(shutdown).await {
+        tracing::error!(error = %ex, "web server error");
+    }
 
     Ok(())
 }
 
+/// shutdown_signal - resolves on SIGINT (ctrl-c) or SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(ex) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %ex, "cannot listen to ctrl-c");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(ex) => {
+                tracing::error!(error = %ex, "cannot listen to SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("shutdown signal received, draining connections");
+}
+
 /// handle_rejection
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
-    // Print to server side
-    println!("ERROR - {:?}", err);
+    // Build user message (custom errors first, then the warp rejections by preference)
+    let mut fields: &[model::FieldError] = &[];
+    let mut index = None;
+    let (status, typ, code, message) = if let Some(err) = err.find::<WebErrorMessage>() {
+        fields = &err.fields;
+        index = err.index;
+        (err.status, err.typ, err.code, err.message.to_string())
+    } else if let Some(ex) = err.find::<warp::body::BodyDeserializeError>() {
+        let status = StatusCode::BAD_REQUEST;
+        (status, "warp", "INVALID_BODY", ex.to_string())
+    } else if let Some(ex) = err.find::<warp::reject::InvalidQuery>() {
+        let status = StatusCode::BAD_REQUEST;
+        (status, "warp", "INVALID_QUERY", ex.to_string())
+    } else if let Some(ex) = err.find::<warp::reject::UnsupportedMediaType>() {
+        let status = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+        (status, "warp", "UNSUPPORTED_MEDIA_TYPE", ex.to_string())
+    } else if let Some(ex) = err.find::<warp::reject::PayloadTooLarge>() {
+        let status = StatusCode::PAYLOAD_TOO_LARGE;
+        (status, "warp", "PAYLOAD_TOO_LARGE", ex.to_string())
+    } else if let Some(ex) = err.find::<warp::reject::MethodNotAllowed>() {
+        let status = StatusCode::METHOD_NOT_ALLOWED;
+        (status, "warp", "METHOD_NOT_ALLOWED", ex.to_string())
+    } else if err.is_not_found() {
+        let status = StatusCode::NOT_FOUND;
+        (status, "warp", "NOT_FOUND", "Not found".to_string())
+    } else {
+        let status = StatusCode::INTERNAL_SERVER_ERROR;
+        (status, "warp", "INTERNAL", String::new())
+    };
 
-    // TODO - Call log API for capture and store
+    metrics::observe_rejection(typ);
 
-    // Build user message
-    let user_message = match err.find::<WebErrorMessage>() {
-        Some(err) => err.typ.to_string(),
-        None => "Unknown".to_string(),
+    // error event (in the request span), with the details for the server errors
+    if status.is_server_error() {
+        tracing::error!(status = status.as_u16(), code, error = ?err, "request failed");
+    } else {
+        tracing::info!(status = status.as_u16(), code, reason = %message, "request rejected");
+    }
+
+    // server error details stay server side
+    let message = if status.is_server_error() {
+        "Internal server error".to_string()
+    } else {
+        message
     };
 
-    let result = json!({ "errorMessage": user_message });
+    let mut result = json!({ "errorMessage": typ, "code": code, "message": message });
+    if !fields.is_empty() {
+        result["fields"] = json!(fields);
+    }
+    if let Some(index) = index {
+        result["index"] = json!(index);
+    }
     let result = warp::reply::json(&result);
 
-    Ok(warp::reply::with_status(
-        result,
-        warp::http::StatusCode::BAD_REQUEST,
-    ))
+    Ok(warp::reply::with_status(result, status))
+}
+
+/// json_response
+fn json_response<D: Serialize>(data: D) -> Result<Json, warp::Rejection> {
+    let response = json!({ "data": data });
+    Ok(warp::reply::json(&response))
+}
+
+/// json_page_response - with the cursor of the next page (null on the last page)
+fn json_page_response<D: Serialize>(
+    data: D,
+    next: Option<String>,
+) -> Result<Json, warp::Rejection> {
+    let response = json!({ "data": data, "next": next });
+    Ok(warp::reply::json(&response))
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -64,8 +209,14 @@ pub enum Error {
     #[error("Web server failed to start because web-folder '{0}' not found.")]
     FailStartWebFolderNotFound(String),
 
+    #[error("Web server failed to start because '{0}' cannot be bound. Cause: {1}")]
+    FailStartBind(String, String),
+
     #[error("Fail authentication missing X-Auth-Token header.")]
     FailAuthMissingXAuth,
+
+    #[error("Register failed, username and password cannot be empty.")]
+    RegisterEmptyCredentials,
 }
 
 // region:    Warp Custom Error
@@ -73,33 +224,120 @@ pub enum Error {
 /// WebErrorMessage
 pub struct WebErrorMessage {
     pub typ: &'static str,
+    pub status: StatusCode,
+    pub code: &'static str, // stable machine-readable code (e.g. ENTITY_NOT_FOUND)
     pub message: String,
+    pub fields: Vec<model::FieldError>, // validation errors
+    pub index: Option<usize>,           // failed operation of a batch
 }
 impl warp::reject::Reject for WebErrorMessage {}
 
 impl WebErrorMessage {
-    /// Create new WebErrorMessage
-    pub fn rejection(typ: &'static str, message: String) -> warp::Rejection {
-        warp::reject::custom(WebErrorMessage { typ, message })
+    /// Create new WebErrorMessage with a given http status and error code
+    pub fn rejection(
+        typ: &'static str,
+        status: StatusCode,
+        code: &'static str,
+        message: String,
+    ) -> warp::Rejection {
+        warp::reject::custom(WebErrorMessage {
+            typ,
+            status,
+            code,
+            message,
+            fields: Vec::new(),
+            index: None,
+        })
     }
 }
 
 impl From<self::Error> for warp::Rejection {
     /// Convert from self::Error to warp::Rejection
     fn from(other: self::Error) -> Self {
-        WebErrorMessage::rejection("web::Error", format!("{}", other))
+        let (status, code) = match other {
+            Error::FailAuthMissingXAuth => (StatusCode::UNAUTHORIZED, "AUTH_MISSING"),
+            Error::RegisterEmptyCredentials => (StatusCode::UNPROCESSABLE_ENTITY, "VALIDATION"),
+            Error::FailStartWebFolderNotFound(_) | Error::FailStartBind(..) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL")
+            }
+        };
+        WebErrorMessage::rejection("web::Error", status, code, format!("{}", other))
     }
 }
 impl From<model::Error> for warp::Rejection {
     /// Convert from model::Error to warp::Rejection
     fn from(other: model::Error) -> Self {
-        WebErrorMessage::rejection("model::Error", format!("{}", other))
+        let (status, code) = model_error_status(&other);
+        let message = format!("{}", other);
+        // the validation errors, also of the failed operation of a batch (with its index)
+        let (fields, index) = match other {
+            model::Error::Validation(fields) => (fields, None),
+            model::Error::BatchOp(index, error) => match *error {
+                model::Error::Validation(fields) => (fields, Some(index)),
+                _ => (Vec::new(), Some(index)),
+            },
+            _ => (Vec::new(), None),
+        };
+        warp::reject::custom(WebErrorMessage {
+            typ: "model::Error",
+            status,
+            code,
+            message,
+            fields,
+            index,
+        })
+    }
+}
+impl From<mail::Error> for warp::Rejection {
+    /// Convert from mail::Error to warp::Rejection
+    fn from(other: mail::Error) -> Self {
+        let (status, code) = match other {
+            mail::Error::InvalidAddress(_) => (StatusCode::UNPROCESSABLE_ENTITY, "INVALID_EMAIL"),
+            mail::Error::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED"),
+            _ => (StatusCode::BAD_GATEWAY, "MAIL_FAILED"),
+        };
+        WebErrorMessage::rejection("mail::Error", status, code, format!("{}", other))
     }
 }
 impl From<security::Error> for warp::Rejection {
     /// Convert from security::Error to warp::Rejection
     fn from(other: security::Error) -> Self {
-        WebErrorMessage::rejection("model::Error", format!("{}", other))
+        let (status, code) = match &other {
+            security::Error::TokenExpired => (StatusCode::UNAUTHORIZED, "TOKEN_EXPIRED"),
+            security::Error::TokenTampered => (StatusCode::UNAUTHORIZED, "TOKEN_INVALID"),
+            security::Error::TokenRevoked => (StatusCode::UNAUTHORIZED, "TOKEN_REVOKED"),
+            security::Error::FailLogin => (StatusCode::UNAUTHORIZED, "LOGIN_FAILED"),
+            security::Error::PwdHash(_) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL"),
+            security::Error::Model(model_error) => model_error_status(model_error),
+        };
+        WebErrorMessage::rejection("security::Error", status, code, format!("{}", other))
+    }
+}
+
+/// model_error_status - http status and error code of a model::Error
+fn model_error_status(error: &model::Error) -> (StatusCode, &'static str) {
+    match error {
+        model::Error::EntityNotFound(..) => (StatusCode::NOT_FOUND, "ENTITY_NOT_FOUND"),
+        model::Error::UserNotAuthorized(..) => (StatusCode::FORBIDDEN, "USER_NOT_AUTHORIZED"),
+        model::Error::BudgetExceeded(..) => (StatusCode::UNPROCESSABLE_ENTITY, "BUDGET_EXCEEDED"),
+        model::Error::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "VALIDATION"),
+        model::Error::BatchOp(_, error) => model_error_status(error),
+        // 23505 - unique_violation (e.g. username already taken)
+        model::Error::Sqlx(sqlx::Error::Database(db_error))
+            if db_error.code().as_deref() == Some("23505") =>
+        {
+            (StatusCode::CONFLICT, "ALREADY_EXISTS")
+        }
+        model::Error::Sqlx(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR"),
+        model::Error::MigrationChecksum(..)
+        | model::Error::MigrationInvalid(..)
+        | model::Error::MigrationNotApplied(..)
+        | model::Error::SqlScript(..)
+        | model::Error::IO(_) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL"),
     }
 }
 // endregion: Warp Custom Error
+
+#[cfg(test)]
+#[path = "../_tests/web_server.rs"]
+mod tests;
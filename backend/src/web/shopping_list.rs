@@ -0,0 +1,278 @@
+use super::{filter_auth::do_auth, filter_utils::with_db, json_response, metrics::route};
+use crate::{
+    model::{
+        BudgetMac, Db, GroceryMac, GroceryPatch, ListMemberMac, ListRole, ShoppingListMac,
+        ShoppingListPatch, UserMac,
+    },
+    security::UserCtx,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use warp::{reply::Json, Filter};
+
+/// Invitation - body of a member invitation
+#[derive(Debug, Deserialize)]
+pub struct Invitation {
+    pub username: String,
+    pub role: ListRole,
+}
+
+/// shopping list REST API
+pub fn shopping_list_rest_filters(
+    base_path: &'static str,
+    db: Arc<Db>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let lists_path = warp::path(base_path).and(warp::path("lists")); // /api/lists
+    let common = with_db(db.clone()).and(do_auth(db.clone()));
+
+    // LIST shopping lists `GET lists/`
+    let list = lists_path
+        .and(warp::get())
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_list);
+
+    // GET shopping list `GET /lists/10`
+    let get = lists_path
+        .and(warp::get())
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_get);
+
+    // CREATE shopping list `POST /lists with body ShoppingListPatch`
+    let create = lists_path
+        .and(warp::post())
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(shopping_list_create);
+
+    // UPDATE shopping list `PATCH /lists/10 with body ShoppingListPatch`
+    let update = lists_path
+        .and(warp::patch())
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(shopping_list_update);
+
+    // DELETE shopping list `DELETE /lists/10`
+    let delete = lists_path
+        .and(warp::delete())
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_delete);
+
+    // LIST groceries of a shopping list `GET /lists/10/groceries`
+    let grocery_list = lists_path
+        .and(warp::get())
+        .and(warp::path::param())
+        .and(warp::path("groceries"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/groceries", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_grocery_list);
+
+    // CREATE grocery in a shopping list `POST /lists/10/groceries with body GroceryPatch`
+    let grocery_create = lists_path
+        .and(warp::post())
+        .and(warp::path::param())
+        .and(warp::path("groceries"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/groceries", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(shopping_list_grocery_create);
+
+    // LIST members of a shopping list `GET /lists/10/members`
+    let member_list = lists_path
+        .and(warp::get())
+        .and(warp::path::param())
+        .and(warp::path("members"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/members", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_member_list);
+
+    // INVITE member to a shopping list `POST /lists/10/members with body Invitation`
+    let member_invite = lists_path
+        .and(warp::post())
+        .and(warp::path::param())
+        .and(warp::path("members"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/members", base_path)))
+        .and(common.clone())
+        .and(warp::body::json())
+        .and_then(shopping_list_member_invite);
+
+    // REMOVE member of a shopping list `DELETE /lists/10/members/124`
+    let member_remove = lists_path
+        .and(warp::delete())
+        .and(warp::path::param())
+        .and(warp::path("members"))
+        .and(warp::path::param())
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/members/:id", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_member_remove);
+
+    // ACCEPT invitation to a shopping list `POST /lists/10/accept`
+    let accept = lists_path
+        .and(warp::post())
+        .and(warp::path::param())
+        .and(warp::path("accept"))
+        .and(warp::path::end())
+        .and(route(format!("/{}/lists/:id/accept", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_accept);
+
+    // LIST pending invitations of the user `GET /invitations`
+    let invitations = warp::path(base_path)
+        .and(warp::path("invitations"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(route(format!("/{}/invitations", base_path)))
+        .and(common.clone())
+        .and_then(shopping_list_invitations);
+
+    list.or(get)
+        .or(create)
+        .or(update)
+        .or(delete)
+        .or(grocery_list)
+        .or(grocery_create)
+        .or(member_list)
+        .or(member_invite)
+        .or(member_remove)
+        .or(accept)
+        .or(invitations)
+}
+
+/// GET - `lists/`
+async fn shopping_list_list(db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let shopping_lists = ShoppingListMac::list(&db, &utx).await?;
+    json_response(shopping_lists)
+}
+
+/// GET - `lists/10`
+async fn shopping_list_get(id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let shopping_list = ShoppingListMac::get(&db, &utx, id).await?;
+    json_response(shopping_list)
+}
+
+/// CREATE - `lists/` with body `ShoppingListPatch`
+async fn shopping_list_create(
+    db: Arc<Db>,
+    utx: UserCtx,
+    patch: ShoppingListPatch,
+) -> Result<Json, warp::Rejection> {
+    let shopping_list = ShoppingListMac::create(&db, &utx, patch).await?;
+    json_response(shopping_list)
+}
+
+/// PATCH - `lists/10` with body `ShoppingListPatch`
+async fn shopping_list_update(
+    id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+    patch: ShoppingListPatch,
+) -> Result<Json, warp::Rejection> {
+    let shopping_list = ShoppingListMac::update(&db, &utx, id, patch).await?;
+    json_response(shopping_list)
+}
+
+/// DELETE - `lists/10`
+async fn shopping_list_delete(id: i64, db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let shopping_list = ShoppingListMac::delete(&db, &utx, id).await?;
+    json_response(shopping_list)
+}
+
+/// GET - `lists/10/groceries`
+async fn shopping_list_grocery_list(
+    list_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+) -> Result<Json, warp::Rejection> {
+    let groceries = GroceryMac::list_by_list_id(&db, &utx, list_id).await?;
+    json_response(groceries)
+}
+
+/// CREATE - `lists/10/groceries` with body `GroceryPatch`
+async fn shopping_list_grocery_create(
+    list_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+    patch: GroceryPatch,
+) -> Result<Json, warp::Rejection> {
+    let patch = GroceryPatch {
+        list_id: Some(list_id),
+        ..patch
+    };
+    let grocery = GroceryMac::create(&db, &utx, patch).await?;
+    let grocery = BudgetMac::with_status(&db, grocery).await?;
+    json_response(grocery)
+}
+
+/// GET - `lists/10/members`
+async fn shopping_list_member_list(
+    list_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+) -> Result<Json, warp::Rejection> {
+    let members = ListMemberMac::list(&db, &utx, list_id).await?;
+    json_response(members)
+}
+
+/// POST - `lists/10/members` with body `Invitation`
+async fn shopping_list_member_invite(
+    list_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+    invitation: Invitation,
+) -> Result<Json, warp::Rejection> {
+    // Note: owner check first, a non owner cannot probe the usernames (404 vs 403)
+    ListMemberMac::require_owner(&*db, &utx, list_id).await?;
+    let user = UserMac::get_by_username(&db, &invitation.username).await?;
+    let member = ListMemberMac::invite(&db, &utx, list_id, user.id, invitation.role).await?;
+    json_response(member)
+}
+
+/// DELETE - `lists/10/members/124`
+async fn shopping_list_member_remove(
+    list_id: i64,
+    user_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+) -> Result<Json, warp::Rejection> {
+    let member = ListMemberMac::remove(&db, &utx, list_id, user_id).await?;
+    json_response(member)
+}
+
+/// POST - `lists/10/accept`
+async fn shopping_list_accept(
+    list_id: i64,
+    db: Arc<Db>,
+    utx: UserCtx,
+) -> Result<Json, warp::Rejection> {
+    let member = ListMemberMac::accept(&db, &utx, list_id).await?;
+    json_response(member)
+}
+
+/// GET - `invitations`
+async fn shopping_list_invitations(db: Arc<Db>, utx: UserCtx) -> Result<Json, warp::Rejection> {
+    let invitations = ListMemberMac::list_invitations(&db, &utx).await?;
+    json_response(invitations)
+}
+
+// region:    Test
+#[cfg(test)]
+#[path = "../_tests/web_shopping_list.rs"]
+mod tests;
+// endregion: Test
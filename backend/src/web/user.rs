@@ -0,0 +1,148 @@
+use super::filter_auth::{do_auth, HEADER_XAUTH};
+use super::{filter_utils::with_db, json_response, metrics::route};
+use crate::{
+    model::{self, Db, FieldError, UserMac, UserPatch},
+    security::{
+        self, end_session, hash_pwd_blocking, new_session_token, rotate_session,
+        verify_pwd_blocking, UserCtx,
+    },
+    web::Error,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use warp::{reply::Json, Filter};
+
+/// Credentials - body of login and register
+#[derive(Debug, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub pwd: String,
+}
+
+// bounds the argon2 work of a login or register
+const PWD_MAX_LEN: usize = 1024;
+
+impl Credentials {
+    /// username - the normalized username (trimmed), the same for login and register
+    fn username(&self) -> &str {
+        self.username.trim()
+    }
+}
+
+/// user REST API (login, logout, register)
+pub fn user_rest_filters(
+    base_path: &'static str,
+    db: Arc<Db>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let api_path = warp::path(base_path);
+
+    // LOGIN `POST /api/login with body Credentials`
+    let login = api_path
+        .and(warp::path("login"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(route(format!("/{}/login", base_path)))
+        .and(with_db(db.clone()))
+        .and(warp::body::json())
+        .and_then(user_login);
+
+    // LOGOUT `POST /api/logout`
+    let logout = api_path
+        .and(warp::path("logout"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(route(format!("/{}/logout", base_path)))
+        .and(with_db(db.clone()))
+        .and(do_auth(db.clone()))
+        .and(warp::header::<String>(HEADER_XAUTH))
+        .and_then(user_logout);
+
+    // REFRESH `POST /api/refresh` (revoke the current token and issue a new one)
+    let refresh = api_path
+        .and(warp::path("refresh"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(route(format!("/{}/refresh", base_path)))
+        .and(with_db(db.clone()))
+        .and(do_auth(db.clone()))
+        .and(warp::header::<String>(HEADER_XAUTH))
+        .and_then(user_refresh);
+
+    // REGISTER `POST /api/register with body Credentials`
+    let register = api_path
+        .and(warp::path("register"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(route(format!("/{}/register", base_path)))
+        .and(with_db(db))
+        .and(warp::body::json())
+        .and_then(user_register);
+
+    login.or(logout).or(refresh).or(register)
+}
+
+/// POST - `login` with body `Credentials`
+async fn user_login(db: Arc<Db>, creds: Credentials) -> Result<Json, warp::Rejection> {
+    // Note: a too long pwd cannot match a registered one
+    if creds.pwd.len() > PWD_MAX_LEN {
+        return Err(security::Error::FailLogin.into());
+    }
+
+    // Note: an unknown username is verified against a dummy hash (same timing as a wrong pwd)
+    let user = match UserMac::get_for_auth(&db, creds.username()).await {
+        Ok(user) => Some(user),
+        Err(model::Error::EntityNotFound(_, _)) => None,
+        Err(ex) => return Err(ex.into()),
+    };
+    let user_id = user.as_ref().map(|user| user.id);
+    verify_pwd_blocking(creds.pwd, user.map(|user| user.pwd)).await?;
+    let user_id = user_id.ok_or(security::Error::FailLogin)?;
+
+    let token = new_session_token(&db, user_id).await?;
+    let user = UserMac::get(&db, user_id).await?;
+    json_response(json!({ "token": token, "user": user }))
+}
+
+/// POST - `logout`
+async fn user_logout(db: Arc<Db>, _utx: UserCtx, token: String) -> Result<Json, warp::Rejection> {
+    end_session(&db, &token).await?;
+    json_response(true)
+}
+
+/// POST - `refresh`
+async fn user_refresh(db: Arc<Db>, utx: UserCtx, token: String) -> Result<Json, warp::Rejection> {
+    let token = rotate_session(&db, &token).await?;
+    let user = UserMac::get(&db, utx.user_id).await?;
+    json_response(json!({ "token": token, "user": user }))
+}
+
+/// POST - `register` with body `Credentials`
+async fn user_register(db: Arc<Db>, creds: Credentials) -> Result<Json, warp::Rejection> {
+    let username = creds.username().to_string();
+    if username.is_empty() || creds.pwd.is_empty() {
+        return Err(Error::RegisterEmptyCredentials.into());
+    }
+    if creds.pwd.len() > PWD_MAX_LEN {
+        let error = FieldError::new(
+            "pwd",
+            format!("cannot be longer than {} bytes", PWD_MAX_LEN),
+        );
+        return Err(model::Error::Validation(vec![error]).into());
+    }
+
+    let data = UserPatch {
+        username: Some(username),
+        pwd: Some(hash_pwd_blocking(creds.pwd).await?),
+    };
+    let user = UserMac::create(&db, data).await?;
+
+    let token = new_session_token(&db, user.id).await?;
+    json_response(json!({ "token": token, "user": user }))
+}
+
+// region:    Test
+#[cfg(test)]
+#[path = "../_tests/web_user.rs"]
+mod tests;
+// endregion: Test
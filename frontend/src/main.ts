@@ -1,2 +1,3 @@
 import './ui/commons.js';
+import './ui/auth-mvc.js';
 import './ui/grocery-mvc.js';
\ No newline at end of file
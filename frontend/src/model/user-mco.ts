@@ -0,0 +1,45 @@
+import { hub } from 'dom-native';
+import { getAuthToken, setAuthToken, webPost } from '../webc';
+
+export interface User {
+    id: number;
+    username: string;
+}
+
+class UserMco {
+
+    isLoggedIn(): boolean {
+        return getAuthToken() != null;
+    }
+
+    async login(username: string, pwd: string): Promise<User> {
+        return this.#authenticate('login', username, pwd);
+    }
+
+    async register(username: string, pwd: string): Promise<User> {
+        return this.#authenticate('register', username, pwd);
+    }
+
+    async logout() {
+        try {
+            // to server (revoke the session)
+            await webPost('logout', {});
+        } finally {
+            setAuthToken(null);
+            // event
+            hub('authHub').pub('Auth', 'logout', null);
+        }
+    }
+
+    async #authenticate(path: 'login' | 'register', username: string, pwd: string): Promise<User> {
+        // to server
+        const data = await webPost(path, { username, pwd });
+        setAuthToken(data.token);
+        // event
+        hub('authHub').pub('Auth', 'login', data.user);
+
+        return data.user as User;
+    }
+}
+
+export const userMco = new UserMco();
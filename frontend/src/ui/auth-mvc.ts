@@ -0,0 +1,99 @@
+import { BaseHTMLElement, customElement, getChildren, html, OnEvent, onEvent, onHub } from "dom-native";
+import { userMco } from "src/model/user-mco";
+
+@customElement("app-mvc")
+class AppMvc extends BaseHTMLElement { // extends HTMLElement
+    init() {
+        this.refresh();
+    }
+
+    refresh() {
+        // the groceries need a session, the login form otherwise
+        let htmlContent: DocumentFragment = userMco.isLoggedIn()
+            ? html`<button class="logout">Logout</button><grocery-mvc></grocery-mvc>`
+            : html`<login-form></login-form>`;
+
+        this.innerHTML = '';
+        this.append(htmlContent);
+    }
+
+    // #region    --- UI Events
+    @onEvent('click', 'button.logout')
+    onLogoutClick() {
+        userMco.logout();
+    }
+    // #endregion --- UI Events
+
+    // #region    --- Data Events
+    @onHub('authHub', 'Auth', 'login')
+    onLogin() {
+        this.refresh();
+    }
+
+    @onHub('authHub', 'Auth', 'logout')
+    onLogout() {
+        this.refresh();
+    }
+    // #endregion --- Data Events
+}
+
+@customElement("login-form")
+class LoginForm extends BaseHTMLElement { // extends HTMLElement
+    #usernameEl!: HTMLInputElement;
+    #pwdEl!: HTMLInputElement;
+    #errorEl!: HTMLElement;
+
+    init() {
+        let htmlContent = html`
+            <h1>Shopping List</h1>
+            <input type="text" class="username" placeholder="Username" autocomplete="username">
+            <input type="password" class="pwd" placeholder="Password" autocomplete="current-password">
+            <div class="error"></div>
+            <div class="actions">
+                <button data-action="login">Login</button>
+                <button data-action="register">Register</button>
+            </div>
+        `;
+        [this.#usernameEl, this.#pwdEl, this.#errorEl] = getChildren(htmlContent, 'input.username', 'input.pwd', 'div.error') as [HTMLInputElement, HTMLInputElement, HTMLElement];
+
+        this.append(htmlContent);
+    }
+
+    // #region    --- UI Events
+    @onEvent('click', 'button')
+    onButtonClick(evt: MouseEvent & OnEvent) {
+        const action = (evt.selectTarget as HTMLElement).dataset.action;
+        this.submit(action == 'register' ? 'register' : 'login');
+    }
+
+    @onEvent('keyup', 'input')
+    onInputKeyUp(evt: KeyboardEvent) {
+        if (evt.key == "Enter") {
+            this.submit('login');
+        }
+    }
+    // #endregion --- UI Events
+
+    async submit(action: 'login' | 'register') {
+        const username = this.#usernameEl.value.trim();
+        const pwd = this.#pwdEl.value;
+        if (username.length == 0 || pwd.length == 0) {
+            this.#errorEl.textContent = "Username and password are required";
+            return;
+        }
+
+        this.#errorEl.textContent = '';
+        try {
+            // on success, app-mvc shows the groceries (authHub login event)
+            await (action == 'register' ? userMco.register(username, pwd) : userMco.login(username, pwd));
+        } catch (ex: any) {
+            this.#errorEl.textContent = ex.message;
+        }
+    }
+}
+// login-form tag
+declare global {
+    interface HTMLElementTagNameMap {
+        'login-form': LoginForm;
+    }
+}
@@ -1,6 +1,10 @@
+import { hub } from 'dom-native';
+
 type WebMethod = "GET" | "POST" | "DELETE" | "PATCH";
 
 const API_BASE_PATH = '/api';
+const HEADER_XAUTH = 'X-Auth-Token';
+const AUTH_TOKEN_KEY = 'authToken'; // localStorage key of the session token
 
 export async function webGet(path: string, data?: any) {
     return webCall("GET", path, data);
@@ -15,20 +19,48 @@ export async function webDelete(path: string, data?: any) {
     return webCall("DELETE", path, data);
 }
 
+// #region    --- Auth Token
+export function getAuthToken(): string | null {
+    return localStorage.getItem(AUTH_TOKEN_KEY);
+}
+
+export function setAuthToken(token: string | null) {
+    if (token == null) {
+        localStorage.removeItem(AUTH_TOKEN_KEY);
+    } else {
+        localStorage.setItem(AUTH_TOKEN_KEY, token);
+    }
+}
+// #endregion --- Auth Token
+
 async function webCall(httpMethod: WebMethod, path: string, data?: any) {
     const url = `${API_BASE_PATH}/${path}`;
 
+    const headers: Record<string, string> = {
+        'Content-Type': 'application/json'
+    };
+    const token = getAuthToken();
+    if (token != null) {
+        headers[HEADER_XAUTH] = token;
+    }
+
     const response = await fetch(url, {
         method: httpMethod,
         mode: 'same-origin',
         cache: 'no-cache',
-        headers: {
-            'Content-Type': 'application/json',
-            'X-Auth-Token': '123'
-        },
+        headers,
         body: JSON.stringify(data)
     });
 
+    // session expired or revoked, back to the login
+    if (response.status == 401 && token != null) {
+        setAuthToken(null);
+        hub('authHub').pub('Auth', 'logout', null);
+    }
+
     let res = await response.json();
+    if (!response.ok) {
+        throw new Error(res.message ?? `${httpMethod} ${url} failed (${response.status})`);
+    }
     return res.data;
-}
\ No newline at end of file
+}